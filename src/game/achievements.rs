@@ -0,0 +1,459 @@
+use bevy::{prelude::*, render::view::RenderLayers, ui::ContentSize};
+
+use crate::{
+    canvas::{
+        text::{SyltText, SyltTextAlign, SyltTextStyle},
+        ui_canvas::SyltUiText,
+    },
+    game::{
+        currency::SparkAmount,
+        simulation::{
+            NodeTriggered, ReversibleAction, SimulationEvent, SparkSimulation,
+            SparksEarned,
+        },
+        system_set::SyltPausableSystems,
+    },
+    i18n::SyltI18nText,
+    routes::SyltRouterState,
+    signals::SyltSignal,
+    ui::constants::SU4,
+};
+
+const ACHIEVEMENTS_FILE: &str = "achievements.yaml";
+/// Seconds an unlock notification stays on screen
+const NOTIFICATION_DURATION: f32 = 4.;
+
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<AchievementUnlocked>();
+        app.init_resource::<UnlockedAchievements>();
+
+        app.add_systems(Startup, trigger_load_achievements);
+
+        app.add_systems(Update, handle_achievements_loaded);
+
+        app.add_systems(
+            OnEnter(SyltRouterState::Game),
+            spawn_notification_container,
+        );
+
+        app.add_systems(
+            Update,
+            (
+                check_achievements,
+                spawn_notifications,
+                despawn_notifications,
+            )
+                .chain()
+                .in_set(SyltPausableSystems)
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<SparkSimulation>),
+                ),
+        );
+    }
+}
+
+/// What has to happen for an achievement to unlock
+#[derive(Debug, Clone, PartialEq)]
+pub enum AchievementCondition {
+    /// Build a node of a type
+    Build(&'static str),
+    /// Earn this many sparks in a single game
+    LifetimeSparks(f64),
+    /// A pulse chain reaching this many nodes
+    ChainDepth(u32),
+    /// Every cell of the grid holds a node
+    FullGrid,
+}
+
+/// The simulation events achievements are checked against
+#[derive(Debug, Clone, Copy)]
+pub enum AchievementEvent<'a> {
+    Simulation(&'a SimulationEvent),
+    Earned(&'a SparksEarned),
+    Triggered(&'a NodeTriggered),
+}
+
+impl AchievementCondition {
+    /// Whether the event, with the simulation after it, meets the condition
+    pub fn is_met(
+        &self,
+        event: AchievementEvent,
+        simulation: &SparkSimulation,
+    ) -> bool {
+        match (self, event) {
+            (
+                Self::Build(kind),
+                AchievementEvent::Simulation(
+                    SimulationEvent::Built { kind: built, .. }
+                    | SimulationEvent::Redone(ReversibleAction::Build {
+                        kind: built,
+                        ..
+                    }),
+                ),
+            ) => kind == built,
+            (Self::LifetimeSparks(amount), AchievementEvent::Earned(_)) => {
+                simulation.lifetime_sparks >= SparkAmount::from(*amount)
+            }
+            (
+                Self::ChainDepth(depth),
+                AchievementEvent::Triggered(triggered),
+            ) => triggered.depth >= *depth,
+            (
+                Self::FullGrid,
+                AchievementEvent::Simulation(
+                    SimulationEvent::Built { .. }
+                    | SimulationEvent::Moved { .. }
+                    | SimulationEvent::Redone(ReversibleAction::Build { .. }),
+                ),
+            ) => simulation.nodes.len() >= simulation.open_cells(),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AchievementDefinition {
+    pub id: &'static str,
+    pub i18n_key: &'static str,
+    pub description_key: &'static str,
+    pub condition: AchievementCondition,
+}
+
+/// Every achievement in the game, in the order they are listed
+pub const ACHIEVEMENTS: &[AchievementDefinition] = &[
+    AchievementDefinition {
+        id: "first_stimulator",
+        i18n_key: "first stimulator achievement",
+        description_key: "first stimulator achievement description",
+        condition: AchievementCondition::Build("stimulator"),
+    },
+    AchievementDefinition {
+        id: "thousand_sparks",
+        i18n_key: "thousand sparks achievement",
+        description_key: "thousand sparks achievement description",
+        condition: AchievementCondition::LifetimeSparks(1e3),
+    },
+    AchievementDefinition {
+        id: "million_sparks",
+        i18n_key: "million sparks achievement",
+        description_key: "million sparks achievement description",
+        condition: AchievementCondition::LifetimeSparks(1e6),
+    },
+    AchievementDefinition {
+        id: "chain_of_five",
+        i18n_key: "chain of five achievement",
+        description_key: "chain of five achievement description",
+        condition: AchievementCondition::ChainDepth(5),
+    },
+    AchievementDefinition {
+        id: "full_grid",
+        i18n_key: "full grid achievement",
+        description_key: "full grid achievement description",
+        condition: AchievementCondition::FullGrid,
+    },
+];
+
+/// Sent once when an achievement is unlocked, with its id
+#[derive(Event, Debug, Clone)]
+pub struct AchievementUnlocked(pub &'static str);
+
+/// Ids of the unlocked achievements, shared by every game
+#[derive(
+    Resource, Debug, Clone, Default, serde::Deserialize, serde::Serialize,
+)]
+pub struct UnlockedAchievements(pub Vec<String>);
+
+impl UnlockedAchievements {
+    pub fn contains(&self, id: &str) -> bool {
+        self.0.iter().any(|unlocked| unlocked == id)
+    }
+}
+
+fn check_achievements(
+    mut simulation_events: EventReader<SimulationEvent>,
+    mut sparks_earned: EventReader<SparksEarned>,
+    mut node_triggered: EventReader<NodeTriggered>,
+    simulation: Res<SparkSimulation>,
+    mut unlocked: ResMut<UnlockedAchievements>,
+    mut unlocked_writer: EventWriter<AchievementUnlocked>,
+    mut sylt_signal_writer: EventWriter<SyltSignal>,
+) {
+    let events: Vec<AchievementEvent> = simulation_events
+        .read()
+        .map(AchievementEvent::Simulation)
+        .chain(sparks_earned.read().map(AchievementEvent::Earned))
+        .chain(node_triggered.read().map(AchievementEvent::Triggered))
+        .collect();
+    if events.is_empty() {
+        return;
+    }
+
+    let mut changed = false;
+
+    for achievement in ACHIEVEMENTS {
+        if unlocked.contains(achievement.id) {
+            continue;
+        }
+
+        if events
+            .iter()
+            .any(|event| achievement.condition.is_met(*event, &simulation))
+        {
+            unlocked.0.push(achievement.id.to_string());
+            unlocked_writer.write(AchievementUnlocked(achievement.id));
+            changed = true;
+        }
+    }
+
+    if !changed {
+        return;
+    }
+
+    match serde_yaml::to_string(&*unlocked) {
+        Ok(data) => {
+            sylt_signal_writer.write(SyltSignal::SaveFile {
+                key: ACHIEVEMENTS_FILE.into(),
+                data: data.into(),
+            });
+        }
+        Err(e) => warn!("Could not serialize the achievements: {e}"),
+    }
+}
+
+#[derive(Component)]
+struct NotificationContainer;
+
+#[derive(Component)]
+struct AchievementNotification(pub Timer);
+
+fn spawn_notification_container(mut cmd: Commands) {
+    cmd.spawn((
+        StateScoped(SyltRouterState::Game),
+        NotificationContainer,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.),
+            bottom: Val::Px(SU4 * 4.),
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(SU4),
+            ..default()
+        },
+        Pickable::IGNORE,
+    ));
+}
+
+fn spawn_notifications(
+    mut cmd: Commands,
+    mut unlocked_reader: EventReader<AchievementUnlocked>,
+    container: Single<Entity, With<NotificationContainer>>,
+) {
+    for AchievementUnlocked(id) in unlocked_reader.read() {
+        let Some(achievement) = ACHIEVEMENTS
+            .iter()
+            .find(|achievement| achievement.id == *id)
+        else {
+            continue;
+        };
+
+        cmd.entity(*container).with_children(|parent| {
+            parent
+                .spawn((
+                    AchievementNotification(Timer::from_seconds(
+                        NOTIFICATION_DURATION,
+                        TimerMode::Once,
+                    )),
+                    Node {
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                ))
+                .with_children(|notification| {
+                    for (key, font_size) in [
+                        ("achievement unlocked", 20.),
+                        (achievement.i18n_key, 28.),
+                    ] {
+                        notification.spawn((
+                            RenderLayers::layer(1),
+                            SyltUiText,
+                            SyltTextStyle {
+                                font_size,
+                                ..default()
+                            },
+                            SyltTextAlign::Middle,
+                            SyltText::default(),
+                            SyltI18nText::from_key(key),
+                            Node::default(),
+                            ContentSize::default(),
+                        ));
+                    }
+                });
+        });
+    }
+}
+
+/// Notifications stay up for real seconds, whatever the game speed
+fn despawn_notifications(
+    mut cmd: Commands,
+    time: Res<Time<Real>>,
+    notification_q: Query<(Entity, &mut AchievementNotification)>,
+) {
+    for (entity, mut notification) in notification_q {
+        if notification.0.tick(time.delta()).finished() {
+            cmd.entity(entity).despawn();
+        }
+    }
+}
+
+fn trigger_load_achievements(mut sylt_signal_writer: EventWriter<SyltSignal>) {
+    sylt_signal_writer.write(SyltSignal::LoadFile {
+        key: ACHIEVEMENTS_FILE.into(),
+    });
+}
+
+fn handle_achievements_loaded(
+    mut sylt_signal_reader: EventReader<SyltSignal>,
+    mut unlocked: ResMut<UnlockedAchievements>,
+) {
+    for event in sylt_signal_reader.read() {
+        if let SyltSignal::FileLoaded { key, data } = event {
+            if key.to_string() != ACHIEVEMENTS_FILE {
+                continue;
+            }
+
+            match serde_yaml::from_str::<UnlockedAchievements>(data) {
+                Ok(loaded) => *unlocked = loaded,
+                Err(e) => warn!("Could not parse the achievements: {e}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{grid::GridBounds, simulation::EarnedFrom};
+
+    /// A 2x2 board with one cell blocked and one node placed
+    fn simulation() -> SparkSimulation {
+        let mut simulation = SparkSimulation::new(Vec::new(), Vec::new());
+        simulation.bounds = GridBounds {
+            min_x: 0,
+            min_y: 0,
+            max_x: 1,
+            max_y: 1,
+        };
+        simulation.rules.blocked.insert(IVec2::new(1, 1));
+        // Outside the bounds, not one of the cells to fill
+        simulation.rules.blocked.insert(IVec2::new(5, 5));
+        simulation.insert_node("relay", IVec2::new(0, 0));
+        simulation
+    }
+
+    fn built(kind: &str, position: IVec2) -> SimulationEvent {
+        SimulationEvent::Built {
+            kind: kind.to_string(),
+            position,
+            cost: SparkAmount::from(10.),
+        }
+    }
+
+    #[test]
+    fn building_a_node_type() {
+        let simulation = simulation();
+        let condition = AchievementCondition::Build("stimulator");
+
+        let event = built("stimulator", IVec2::ZERO);
+        assert!(
+            condition.is_met(AchievementEvent::Simulation(&event), &simulation)
+        );
+
+        let event = built("relay", IVec2::ZERO);
+        assert!(!condition
+            .is_met(AchievementEvent::Simulation(&event), &simulation));
+
+        let event = SimulationEvent::Redone(ReversibleAction::Build {
+            kind: "stimulator".to_string(),
+            position: IVec2::ZERO,
+            cost: SparkAmount::from(10.),
+        });
+        assert!(
+            condition.is_met(AchievementEvent::Simulation(&event), &simulation)
+        );
+    }
+
+    #[test]
+    fn earning_lifetime_sparks() {
+        let mut simulation = simulation();
+        let condition = AchievementCondition::LifetimeSparks(1e3);
+        let earned = SparksEarned {
+            amount: SparkAmount::from(1.),
+            from: EarnedFrom::Sale,
+        };
+
+        simulation.lifetime_sparks = SparkAmount::from(999.);
+        assert!(
+            !condition.is_met(AchievementEvent::Earned(&earned), &simulation)
+        );
+
+        simulation.lifetime_sparks = SparkAmount::from(1e3);
+        assert!(
+            condition.is_met(AchievementEvent::Earned(&earned), &simulation)
+        );
+    }
+
+    #[test]
+    fn reaching_a_chain_depth() {
+        let simulation = simulation();
+        let condition = AchievementCondition::ChainDepth(10);
+        let triggered = |depth| NodeTriggered {
+            source: IVec2::ZERO,
+            target: IVec2::X,
+            depth,
+        };
+
+        assert!(!condition
+            .is_met(AchievementEvent::Triggered(&triggered(9)), &simulation));
+        assert!(condition
+            .is_met(AchievementEvent::Triggered(&triggered(10)), &simulation));
+    }
+
+    #[test]
+    fn filling_the_grid_around_blocked_cells() {
+        let mut simulation = simulation();
+        let condition = AchievementCondition::FullGrid;
+        let event = built("relay", IVec2::new(1, 0));
+
+        simulation.insert_node("relay", IVec2::new(1, 0));
+        assert!(!condition
+            .is_met(AchievementEvent::Simulation(&event), &simulation));
+
+        simulation.insert_node("relay", IVec2::new(0, 1));
+        assert!(
+            condition.is_met(AchievementEvent::Simulation(&event), &simulation)
+        );
+
+        let event = SimulationEvent::Redone(ReversibleAction::Build {
+            kind: "relay".to_string(),
+            position: IVec2::new(0, 1),
+            cost: SparkAmount::from(10.),
+        });
+        assert!(
+            condition.is_met(AchievementEvent::Simulation(&event), &simulation)
+        );
+
+        // Clicks do not fill the grid
+        let event = SimulationEvent::Clicked {
+            position: IVec2::ZERO,
+        };
+        assert!(!condition
+            .is_met(AchievementEvent::Simulation(&event), &simulation));
+    }
+}
@@ -0,0 +1,172 @@
+use bevy::prelude::*;
+use vello::kurbo::{BezPath, ParamCurveArclen, Point, Stroke};
+
+use crate::{
+    canvas::world_canvas::SyltWorldScene,
+    game::{
+        grid::{CELL_GAP, CELL_HEIGHT, CELL_WIDTH},
+        simulation::{SimulationEvent, SparkSimulation},
+        system_set::{SyltGameSystemSet, SyltPausableSystems},
+    },
+    routes::SyltRouterState,
+    vectors::subpath::extract_subpath_fraction,
+};
+
+/// Share of the connection covered by the travelling glow
+const GLOW_FRACTION: f64 = 0.35;
+/// Beams alive at once, pulses beyond this are not drawn
+const MAX_BEAMS: usize = 128;
+/// Beam colours by chain depth, deeper chains use the last one
+const DEPTH_COLORS: [[f32; 4]; 5] = [
+    [0.3, 0.6, 0.9, 1.0],
+    [0.3, 0.9, 0.8, 1.0],
+    [0.5, 0.9, 0.3, 1.0],
+    [0.9, 0.8, 0.3, 1.0],
+    [0.9, 0.4, 0.3, 1.0],
+];
+
+pub struct BeamsPlugin;
+
+impl Plugin for BeamsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (spawn_beams, draw_beams)
+                .chain()
+                .in_set(SyltPausableSystems)
+                .in_set(SyltGameSystemSet::Update)
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<SparkSimulation>),
+                ),
+        );
+    }
+}
+
+/// A pulse travelling from one node to another
+#[derive(Component)]
+#[require(SyltWorldScene)]
+struct PulseBeam {
+    /// Centre to centre, relative to the cell of the source
+    path: BezPath,
+    timer: Timer,
+    depth: u32,
+}
+
+fn depth_color(depth: u32) -> vello::peniko::Color {
+    let index = (depth.max(1) as usize - 1).min(DEPTH_COLORS.len() - 1);
+    vello::peniko::Color::new(DEPTH_COLORS[index])
+}
+
+fn spawn_beams(
+    mut cmd: Commands,
+    mut simulation_events: EventReader<SimulationEvent>,
+    beam_q: Query<(), With<PulseBeam>>,
+) {
+    let mut free = MAX_BEAMS.saturating_sub(beam_q.iter().count());
+
+    for event in simulation_events.read() {
+        let SimulationEvent::Pulsed {
+            position,
+            targets,
+            depth,
+            delay,
+        } = event
+        else {
+            continue;
+        };
+
+        // Beams of pulses that arrive at once would not be seen
+        if *delay <= 0. {
+            continue;
+        }
+
+        let center =
+            Point::new(CELL_WIDTH as f64 * 0.5, CELL_HEIGHT as f64 * 0.5);
+
+        for target in targets {
+            if free == 0 {
+                return;
+            }
+            free -= 1;
+
+            // Scenes are y-down, like the rows of the grid
+            let offset = *target - *position;
+            let mut path = BezPath::new();
+            path.move_to(center);
+            path.line_to(Point::new(
+                center.x + offset.x as f64 * (CELL_GAP + CELL_WIDTH) as f64,
+                center.y + offset.y as f64 * (CELL_GAP + CELL_HEIGHT) as f64,
+            ));
+
+            cmd.spawn((
+                StateScoped(SyltRouterState::Game),
+                PulseBeam {
+                    path,
+                    timer: Timer::from_seconds(*delay, TimerMode::Once),
+                    depth: *depth,
+                },
+                // Below the nodes, which sit at 100
+                Transform::from_xyz(
+                    position.x as f32 * (CELL_GAP + CELL_WIDTH),
+                    -(position.y as f32) * (CELL_GAP + CELL_HEIGHT),
+                    50.,
+                ),
+            ));
+        }
+    }
+}
+
+fn draw_beams(
+    mut cmd: Commands,
+    time: Res<Time>,
+    beam_q: Query<(Entity, &mut PulseBeam, &mut SyltWorldScene)>,
+) {
+    for (entity, mut beam, mut scene) in beam_q {
+        if beam.timer.tick(time.delta()).finished() {
+            cmd.entity(entity).despawn();
+            continue;
+        }
+
+        let color = depth_color(beam.depth);
+        let length: f64 = beam
+            .path
+            .segments()
+            .map(|segment| segment.arclen(0.1))
+            .sum();
+
+        // The glow leaves the source as the timer starts and reaches the
+        // target as the pulse arrives
+        let start =
+            beam.timer.fraction() as f64 * length * (1. - GLOW_FRACTION);
+        let glow =
+            extract_subpath_fraction(&beam.path, GLOW_FRACTION, start, 0.1);
+
+        let scene = &mut scene.inner;
+        scene.reset();
+
+        scene.stroke(
+            &Stroke::new(2.),
+            vello::kurbo::Affine::default(),
+            color.with_alpha(0.2),
+            None,
+            &beam.path,
+        );
+
+        scene.stroke(
+            &Stroke::new(10.),
+            vello::kurbo::Affine::default(),
+            color.with_alpha(0.3),
+            None,
+            &glow,
+        );
+
+        scene.stroke(
+            &Stroke::new(4.),
+            vello::kurbo::Affine::default(),
+            color,
+            None,
+            &glow,
+        );
+    }
+}
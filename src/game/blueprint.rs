@@ -0,0 +1,561 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use bevy::{
+    platform::collections::HashMap, prelude::*, render::view::RenderLayers,
+    ui::ContentSize,
+};
+use copypasta::ClipboardProvider;
+use thiserror::Error;
+
+use crate::{
+    canvas::{
+        text::{SyltText, SyltTextAlign, SyltTextStyle},
+        ui_canvas::SyltUiText,
+    },
+    game::{
+        build::ShowBuildMenu,
+        catalog::GridDirection,
+        currency::SparkAmount,
+        grid::FocusedGridPosition,
+        node_menu::NodeMenu,
+        simulation::{BuildRequested, SparkSimulation},
+        system_set::{SyltGameSystemSet, SyltPausableSystems},
+        upgrades::UpgradePanel,
+    },
+    i18n::SyltI18nText,
+    routes::SyltRouterState,
+    settings::SyltSettings,
+    ui::{constants::SU4, copy_paste::SyltClipboard},
+};
+
+/// Bumped whenever the encoding changes, older strings are rejected
+const BLUEPRINT_VERSION: u8 = 1;
+/// Seconds a blueprint notice stays on screen
+const NOTICE_DURATION: f32 = 4.;
+
+pub struct BlueprintPlugin;
+
+impl Plugin for BlueprintPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            on_keyboard_press
+                .in_set(SyltPausableSystems)
+                .in_set(SyltGameSystemSet::Input)
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<ShowBuildMenu>)
+                        .and(resource_exists::<NodeMenu>)
+                        .and(resource_exists::<UpgradePanel>)
+                        .and(resource_exists::<FocusedGridPosition>)
+                        .and(resource_exists::<SparkSimulation>),
+                ),
+        );
+
+        app.add_systems(
+            Update,
+            despawn_notices
+                .in_set(SyltPausableSystems)
+                .run_if(in_state(SyltRouterState::Game)),
+        );
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum BlueprintError {
+    #[error("Not a valid blueprint string: {0}")]
+    Encoding(#[from] base64::DecodeError),
+    #[error("Unsupported blueprint version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("The blueprint ends too early")]
+    Truncated,
+    #[error("The blueprint has unexpected trailing data")]
+    TrailingData,
+    #[error("Node kind is not valid text")]
+    InvalidKind,
+    #[error("Node kind index {0} is out of range")]
+    KindOutOfRange(u8),
+    #[error("Unknown facing: {0}")]
+    InvalidFacing(u8),
+    #[error("Node kind can not be built: {0}")]
+    NotBuildable(String),
+    #[error("Too many node kinds to encode: {0}")]
+    TooManyKinds(usize),
+    #[error("Node kind is too long to encode: {0}")]
+    KindTooLong(String),
+    #[error("Too many nodes to encode: {0}")]
+    TooManyNodes(usize),
+    #[error("Node offset can not be encoded: {0}")]
+    OffsetOutOfRange(IVec2),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlueprintNode {
+    pub kind: String,
+    /// Cell relative to the top left corner of the blueprint
+    pub offset: IVec2,
+    pub facing: GridDirection,
+}
+
+/// A grid layout that can be shared as text. Encoded as base64 of the
+/// version, a table of node kinds and four bytes per node: kind index, x, y
+/// and facing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Blueprint {
+    pub nodes: Vec<BlueprintNode>,
+}
+
+impl Blueprint {
+    /// The layout of the placed nodes that can be built, moved to start at
+    /// the origin
+    pub fn capture(simulation: &SparkSimulation) -> Self {
+        let placed: Vec<_> = simulation
+            .nodes
+            .iter()
+            .filter(|(_, node)| {
+                simulation
+                    .definition(&node.kind)
+                    .is_some_and(|definition| definition.buildable)
+            })
+            .collect();
+
+        let min = placed
+            .iter()
+            .map(|(position, _)| **position)
+            .reduce(IVec2::min)
+            .unwrap_or_default();
+
+        let mut nodes: Vec<BlueprintNode> = placed
+            .into_iter()
+            .map(|(position, node)| BlueprintNode {
+                kind: node.kind.clone(),
+                offset: *position - min,
+                facing: node.facing,
+            })
+            .collect();
+        nodes.sort_by_key(|node| (node.offset.y, node.offset.x));
+
+        Self { nodes }
+    }
+
+    /// Fails instead of truncating counts, kinds or offsets that do not fit
+    /// in their bytes
+    pub fn encode(&self) -> Result<String, BlueprintError> {
+        let mut kinds: Vec<&str> = Vec::new();
+        for node in &self.nodes {
+            if !kinds.contains(&node.kind.as_str()) {
+                kinds.push(&node.kind);
+            }
+        }
+
+        let mut bytes = vec![
+            BLUEPRINT_VERSION,
+            u8::try_from(kinds.len())
+                .map_err(|_| BlueprintError::TooManyKinds(kinds.len()))?,
+        ];
+
+        for kind in &kinds {
+            bytes.push(
+                u8::try_from(kind.len()).map_err(|_| {
+                    BlueprintError::KindTooLong(kind.to_string())
+                })?,
+            );
+            bytes.extend_from_slice(kind.as_bytes());
+        }
+
+        let count = u16::try_from(self.nodes.len())
+            .map_err(|_| BlueprintError::TooManyNodes(self.nodes.len()))?;
+        bytes.extend_from_slice(&count.to_le_bytes());
+
+        for node in &self.nodes {
+            let kind = kinds
+                .iter()
+                .position(|kind| *kind == node.kind)
+                .unwrap_or_default();
+            let facing = GridDirection::ALL
+                .iter()
+                .position(|direction| *direction == node.facing)
+                .unwrap_or_default();
+            let (Ok(x), Ok(y)) =
+                (u8::try_from(node.offset.x), u8::try_from(node.offset.y))
+            else {
+                return Err(BlueprintError::OffsetOutOfRange(node.offset));
+            };
+
+            bytes.extend_from_slice(&[kind as u8, x, y, facing as u8]);
+        }
+
+        Ok(URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    pub fn decode(text: &str) -> Result<Self, BlueprintError> {
+        let bytes = URL_SAFE_NO_PAD.decode(text.trim())?;
+        let mut reader = bytes.iter().copied();
+        let mut next = || reader.next().ok_or(BlueprintError::Truncated);
+
+        let version = next()?;
+        if version != BLUEPRINT_VERSION {
+            return Err(BlueprintError::UnsupportedVersion(version));
+        }
+
+        let mut kinds = Vec::new();
+        for _ in 0..next()? {
+            let kind: Vec<u8> =
+                (0..next()?).map(|_| next()).collect::<Result<_, _>>()?;
+            kinds.push(
+                String::from_utf8(kind)
+                    .map_err(|_| BlueprintError::InvalidKind)?,
+            );
+        }
+
+        let count = u16::from_le_bytes([next()?, next()?]);
+
+        let mut nodes = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let [kind, x, y, facing] = [next()?, next()?, next()?, next()?];
+
+            nodes.push(BlueprintNode {
+                kind: kinds
+                    .get(kind as usize)
+                    .ok_or(BlueprintError::KindOutOfRange(kind))?
+                    .clone(),
+                offset: IVec2::new(x as i32, y as i32),
+                facing: *GridDirection::ALL
+                    .get(facing as usize)
+                    .ok_or(BlueprintError::InvalidFacing(facing))?,
+            });
+        }
+
+        if next().is_ok() {
+            return Err(BlueprintError::TrailingData);
+        }
+
+        Ok(Self { nodes })
+    }
+
+    /// What building every node would cost on top of the nodes already
+    /// placed, the price of each type grows with every copy
+    pub fn cost(
+        &self,
+        simulation: &SparkSimulation,
+    ) -> Result<SparkAmount, BlueprintError> {
+        let mut owned: HashMap<&str, usize> = HashMap::default();
+        let mut total = SparkAmount::ZERO;
+
+        for node in &self.nodes {
+            let definition = simulation
+                .buildable()
+                .find(|definition| definition.id == node.kind)
+                .ok_or_else(|| {
+                    BlueprintError::NotBuildable(node.kind.clone())
+                })?;
+
+            let count = owned
+                .entry(&node.kind)
+                .or_insert_with(|| simulation.owned(&node.kind));
+            total += definition.price(*count);
+            *count += 1;
+        }
+
+        Ok(total)
+    }
+
+    /// Build requests for every node with `anchor` as the top left corner.
+    /// Nodes that do not fit or can not be afforded are rejected when the
+    /// requests are applied.
+    pub fn requests(&self, anchor: IVec2) -> Vec<BuildRequested> {
+        self.nodes
+            .iter()
+            .map(|node| BuildRequested {
+                kind: node.kind.clone(),
+                position: anchor + node.offset,
+                facing: node.facing,
+            })
+            .collect()
+    }
+}
+
+#[derive(Component)]
+struct BlueprintNotice(pub Timer);
+
+/// Ctrl+C copies the layout, Ctrl+V places the copied layout at the focused
+/// cell
+#[allow(clippy::too_many_arguments)]
+fn on_keyboard_press(
+    mut cmd: Commands,
+    display_shop: Res<ShowBuildMenu>,
+    node_menu: Res<NodeMenu>,
+    upgrade_panel: Res<UpgradePanel>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focused_grid_position: Res<FocusedGridPosition>,
+    settings: Res<SyltSettings>,
+    mut clipboard: ResMut<SyltClipboard>,
+    simulation: Res<SparkSimulation>,
+    mut build_requests: EventWriter<BuildRequested>,
+    notice_q: Query<Entity, With<BlueprintNotice>>,
+) {
+    if display_shop.0 || *node_menu != NodeMenu::Closed || upgrade_panel.open {
+        return;
+    }
+
+    if !keyboard_input
+        .any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+    {
+        return;
+    }
+
+    let (key, detail) = if keyboard_input.just_pressed(KeyCode::KeyC) {
+        let text = match Blueprint::capture(&simulation).encode() {
+            Ok(text) => text,
+            Err(e) => {
+                warn!("Could not encode the blueprint: {e}");
+                return;
+            }
+        };
+
+        if let Err(e) = clipboard.context.set_contents(text) {
+            warn!("Could not copy the blueprint: {e}");
+            return;
+        }
+
+        ("blueprint copied", None)
+    } else if keyboard_input.just_pressed(KeyCode::KeyV) {
+        let Ok(contents) = clipboard.context.get_contents() else {
+            return;
+        };
+
+        let result = Blueprint::decode(&contents).and_then(|blueprint| {
+            let cost = blueprint.cost(&simulation)?;
+            Ok((blueprint, cost))
+        });
+
+        match result {
+            Ok((blueprint, cost)) => {
+                let anchor = IVec2::new(
+                    focused_grid_position.x,
+                    focused_grid_position.y,
+                );
+                build_requests.write_batch(blueprint.requests(anchor));
+
+                let detail = format!(
+                    "{} ({} SP)",
+                    blueprint.nodes.len(),
+                    cost.format(settings.number_notation.0),
+                );
+
+                ("blueprint placed", Some(detail))
+            }
+            Err(e) => {
+                debug!("Could not read the blueprint: {e}");
+                ("blueprint invalid", None)
+            }
+        }
+    } else {
+        return;
+    };
+
+    for notice in &notice_q {
+        cmd.entity(notice).despawn();
+    }
+
+    let text = |content: String| {
+        (
+            RenderLayers::layer(1),
+            SyltUiText,
+            SyltTextStyle {
+                font_size: 28.,
+                ..default()
+            },
+            SyltTextAlign::Middle,
+            SyltText {
+                content,
+                ..default()
+            },
+            Node::default(),
+            ContentSize::default(),
+        )
+    };
+
+    cmd.spawn((
+        StateScoped(SyltRouterState::Game),
+        BlueprintNotice(Timer::from_seconds(NOTICE_DURATION, TimerMode::Once)),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.),
+            top: Val::Px(SU4 * 4.),
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(SU4),
+            ..default()
+        },
+        Pickable::IGNORE,
+    ))
+    .with_children(|parent| {
+        parent.spawn((text(String::new()), SyltI18nText::from_key(key)));
+
+        if let Some(detail) = detail {
+            parent.spawn(text(detail));
+        }
+    });
+}
+
+/// Ticks with real time so notices also go away at a paused game speed
+fn despawn_notices(
+    mut cmd: Commands,
+    time: Res<Time<Real>>,
+    notice_q: Query<(Entity, &mut BlueprintNotice)>,
+) {
+    for (entity, mut notice) in notice_q {
+        if notice.0.tick(time.delta()).finished() {
+            cmd.entity(entity).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(
+        kind: &str,
+        x: i32,
+        y: i32,
+        facing: GridDirection,
+    ) -> BlueprintNode {
+        BlueprintNode {
+            kind: kind.to_string(),
+            offset: IVec2::new(x, y),
+            facing,
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Blueprint, BlueprintError> {
+        Blueprint::decode(&URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    #[test]
+    fn encoding_round_trips() {
+        let blueprint = Blueprint {
+            nodes: vec![
+                node("relay", 0, 0, GridDirection::North),
+                node("stimulator", 1, 0, GridDirection::SouthWest),
+                node("relay", 24, 24, GridDirection::NorthWest),
+            ],
+        };
+
+        let text = blueprint.encode().unwrap();
+        assert_eq!(Blueprint::decode(&text), Ok(blueprint));
+
+        let empty = Blueprint::default();
+        assert_eq!(Blueprint::decode(&empty.encode().unwrap()), Ok(empty));
+    }
+
+    #[test]
+    fn surrounding_whitespace_is_ignored() {
+        let blueprint = Blueprint {
+            nodes: vec![node("relay", 2, 3, GridDirection::East)],
+        };
+        let text = format!("  {}\n", blueprint.encode().unwrap());
+
+        assert_eq!(Blueprint::decode(&text), Ok(blueprint));
+    }
+
+    #[test]
+    fn values_that_do_not_fit_are_not_encoded() {
+        let long = "x".repeat(256);
+        assert_eq!(
+            Blueprint {
+                nodes: vec![node(&long, 0, 0, GridDirection::North)],
+            }
+            .encode(),
+            Err(BlueprintError::KindTooLong(long))
+        );
+
+        for offset in [IVec2::new(256, 0), IVec2::new(0, -1)] {
+            assert_eq!(
+                Blueprint {
+                    nodes: vec![BlueprintNode {
+                        offset,
+                        ..node("relay", 0, 0, GridDirection::North)
+                    }],
+                }
+                .encode(),
+                Err(BlueprintError::OffsetOutOfRange(offset))
+            );
+        }
+
+        let nodes: Vec<_> = (0..256)
+            .map(|i| node(&i.to_string(), 0, 0, GridDirection::North))
+            .collect();
+        assert_eq!(
+            Blueprint { nodes }.encode(),
+            Err(BlueprintError::TooManyKinds(256))
+        );
+
+        let nodes = vec![node("relay", 0, 0, GridDirection::North); 65_536];
+        assert_eq!(
+            Blueprint { nodes }.encode(),
+            Err(BlueprintError::TooManyNodes(65_536))
+        );
+    }
+
+    #[test]
+    fn invalid_strings_are_rejected() {
+        assert!(matches!(
+            Blueprint::decode("not base64!"),
+            Err(BlueprintError::Encoding(_))
+        ));
+        assert_eq!(decode(&[]), Err(BlueprintError::Truncated));
+        assert_eq!(
+            decode(&[2, 0, 0, 0]),
+            Err(BlueprintError::UnsupportedVersion(2))
+        );
+    }
+
+    #[test]
+    fn truncated_and_trailing_data() {
+        // One kind "a" and one node
+        let valid = [1, 1, 1, b'a', 1, 0, 0, 0, 0, 0];
+        assert!(decode(&valid).is_ok());
+
+        for length in 1..valid.len() {
+            assert_eq!(
+                decode(&valid[..length]),
+                Err(BlueprintError::Truncated)
+            );
+        }
+
+        let mut trailing = valid.to_vec();
+        trailing.push(0);
+        assert_eq!(decode(&trailing), Err(BlueprintError::TrailingData));
+    }
+
+    #[test]
+    fn invalid_nodes_are_rejected() {
+        assert_eq!(
+            decode(&[1, 1, 1, 0xff, 0, 0]),
+            Err(BlueprintError::InvalidKind)
+        );
+        assert_eq!(
+            decode(&[1, 1, 1, b'a', 1, 0, 1, 0, 0, 0]),
+            Err(BlueprintError::KindOutOfRange(1))
+        );
+        assert_eq!(
+            decode(&[1, 1, 1, b'a', 1, 0, 0, 0, 0, 8]),
+            Err(BlueprintError::InvalidFacing(8))
+        );
+    }
+
+    #[test]
+    fn unknown_kinds_can_not_be_built() {
+        let simulation = SparkSimulation::new(Vec::new(), Vec::new());
+        let blueprint = Blueprint {
+            nodes: vec![node("relay", 0, 0, GridDirection::North)],
+        };
+
+        assert_eq!(
+            blueprint.cost(&simulation),
+            Err(BlueprintError::NotBuildable("relay".to_string()))
+        );
+    }
+}
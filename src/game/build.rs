@@ -1,4 +1,5 @@
 use bevy::{prelude::*, render::view::RenderLayers, window::PrimaryWindow};
+use vello::kurbo::Shape;
 
 use crate::{
     canvas::{
@@ -9,22 +10,21 @@ use crate::{
         ui_canvas::{
             NoSyltUiScaling, SyltUiScene, SyltUiText, SyltUiUseWorldCoorindates,
         },
-        world_canvas::SyltWorldCanvasScaleFactor,
+        world_canvas::SyltPickingShape,
     },
     game::{
-        grid::{
-            FocusedGridPosition, GridPosition, CELL_GAP, CELL_HEIGHT,
-            CELL_WIDTH,
-        },
+        catalog::GridDirection,
+        grid::{FocusedGridPosition, CELL_GAP, CELL_HEIGHT, CELL_WIDTH},
         instructions::InstructionState,
-        nodes::{SparkNode, StimulatorNode, STIMULATOR_NODE_COST},
+        node_menu::NodeMenu,
+        simulation::{BuildRequested, SimulationEvent, SparkSimulation},
         system_set::{SyltGameSystemSet, SyltPausableSystems},
-        Sparks,
+        upgrades::UpgradePanel,
     },
     i18n::SyltI18nText,
     menus::SyltMenuState,
     routes::SyltRouterState,
-    vectors::polygon::plot_polygon_path,
+    settings::SyltSettings,
 };
 
 pub struct BuildPlugin;
@@ -40,7 +40,24 @@ impl Plugin for BuildPlugin {
             (on_keyboard_press)
                 .in_set(SyltPausableSystems)
                 .in_set(SyltGameSystemSet::Input)
-                .run_if(in_state(SyltRouterState::Game)),
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<NodeMenu>)
+                        .and(resource_exists::<UpgradePanel>)
+                        .and(resource_exists::<SparkSimulation>),
+                ),
+        );
+
+        app.add_systems(
+            Update,
+            close_shop_on_built
+                .in_set(SyltPausableSystems)
+                .in_set(SyltGameSystemSet::Update)
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<ShowBuildMenu>)
+                        .and(resource_exists::<FocusedGridPosition>),
+                ),
         );
 
         app.add_systems(
@@ -53,11 +70,13 @@ impl Plugin for BuildPlugin {
 
         app.add_systems(
             Update,
-            (draw_shop, draw_overlay)
+            (draw_shop, draw_overlay, update_shop_text)
                 .in_set(SyltPausableSystems)
                 .run_if(
                     resource_exists::<ShowBuildMenu>
-                        .and(resource_equals(ShowBuildMenu(true))),
+                        .and(resource_equals(ShowBuildMenu(true)))
+                        .and(resource_exists::<ShopSelection>)
+                        .and(resource_exists::<SparkSimulation>),
                 ),
         );
     }
@@ -65,75 +84,148 @@ impl Plugin for BuildPlugin {
 
 fn add_shop(mut cmd: Commands) {
     cmd.insert_resource(ShowBuildMenu(false));
+    cmd.insert_resource(ShopSelection(0));
 }
 
 fn remove_shop(mut cmd: Commands) {
     cmd.remove_resource::<ShowBuildMenu>();
+    cmd.remove_resource::<ShopSelection>();
 }
 
 #[derive(Resource, PartialEq)]
 pub struct ShowBuildMenu(pub bool);
 
+/// Index of the highlighted item among the buildable node types
+#[derive(Resource)]
+pub struct ShopSelection(pub usize);
+
+/// The shop closes once the node it was opened for is built, failed
+/// purchases keep it open
+fn close_shop_on_built(
+    mut display_shop: ResMut<ShowBuildMenu>,
+    focused_grid_position: Res<FocusedGridPosition>,
+    mut menu_state: ResMut<NextState<SyltMenuState>>,
+    mut simulation_events: EventReader<SimulationEvent>,
+) {
+    let focused = IVec2::new(focused_grid_position.x, focused_grid_position.y);
+
+    let built = simulation_events.read().any(|event| {
+        matches!(
+            event,
+            SimulationEvent::Built { position, .. } if *position == focused
+        )
+    });
+
+    if built && display_shop.0 {
+        menu_state.set(SyltMenuState::None);
+        display_shop.0 = false;
+    }
+}
+
+const HOTKEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+#[allow(clippy::too_many_arguments)]
 fn on_keyboard_press(
-    mut cmd: Commands,
     mut display_shop: ResMut<ShowBuildMenu>,
+    mut selection: ResMut<ShopSelection>,
+    node_menu: Res<NodeMenu>,
+    upgrade_panel: Res<UpgradePanel>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    spark_node_q: Query<&GridPosition, With<SparkNode>>,
     focused_grid_position: Res<FocusedGridPosition>,
     mut menu_state: ResMut<NextState<SyltMenuState>>,
-    mut sparks: ResMut<Sparks>,
+    simulation: Res<SparkSimulation>,
+    mut build_requests: EventWriter<BuildRequested>,
 ) {
-    if keyboard_input.any_just_pressed([
-        KeyCode::Space,
-        KeyCode::Enter,
-        KeyCode::KeyB,
-        KeyCode::KeyI,
-    ]) {
-        if display_shop.0 {
-            if sparks.0 >= STIMULATOR_NODE_COST {
-                sparks.0 -= STIMULATOR_NODE_COST;
-                let mut animation_timer =
-                    Timer::from_seconds(0.25, TimerMode::Once);
-                animation_timer.pause();
-
-                cmd.spawn((
-                    StateScoped(SyltRouterState::Game),
-                    SparkNode::default(),
-                    GridPosition::new(
-                        focused_grid_position.x,
-                        focused_grid_position.y,
-                    ),
-                    Transform::from_xyz(0., 0., 100.),
-                    SyltWorldCanvasScaleFactor(1.0),
-                    StimulatorNode {
-                        click_timer: Timer::from_seconds(
-                            1.,
-                            TimerMode::Repeating,
-                        ),
-                        ..default()
-                    },
-                ));
-                menu_state.set(SyltMenuState::None);
-                display_shop.0 = false;
-            }
-        } else {
-            for grid_position in &spark_node_q {
-                if grid_position.x == focused_grid_position.x
-                    && grid_position.y == focused_grid_position.y
-                {
-                    return;
-                }
-            }
+    if *node_menu != NodeMenu::Closed || upgrade_panel.open {
+        return;
+    }
 
+    let position = IVec2::new(focused_grid_position.x, focused_grid_position.y);
+
+    if !display_shop.0 {
+        if keyboard_input.any_just_pressed([
+            KeyCode::Space,
+            KeyCode::Enter,
+            KeyCode::KeyB,
+            KeyCode::KeyI,
+        ]) && !simulation.nodes.contains_key(&position)
+            && !simulation.is_blocked(position)
+        {
             menu_state.set(SyltMenuState::Disabled);
             display_shop.0 = true;
         }
+
+        return;
     }
 
-    if display_shop.0 && keyboard_input.just_pressed(KeyCode::Escape) {
+    if keyboard_input.just_pressed(KeyCode::Escape) {
         menu_state.set(SyltMenuState::None);
         display_shop.0 = false;
+        return;
     }
+
+    let item_count = simulation.buildable().count();
+    if item_count == 0 {
+        return;
+    }
+
+    if keyboard_input.any_just_pressed([
+        KeyCode::KeyA,
+        KeyCode::KeyH,
+        KeyCode::ArrowLeft,
+    ]) {
+        selection.0 = (selection.0 + item_count - 1) % item_count;
+    }
+
+    if keyboard_input.any_just_pressed([
+        KeyCode::KeyD,
+        KeyCode::KeyL,
+        KeyCode::ArrowRight,
+    ]) {
+        selection.0 = (selection.0 + 1) % item_count;
+    }
+
+    let hotkey = HOTKEYS
+        .iter()
+        .position(|key| keyboard_input.just_pressed(*key))
+        .filter(|index| *index < item_count);
+
+    let confirmed = keyboard_input.any_just_pressed([
+        KeyCode::Space,
+        KeyCode::Enter,
+        KeyCode::KeyB,
+        KeyCode::KeyI,
+    ]);
+
+    let Some(index) = hotkey.or(confirmed.then_some(selection.0)) else {
+        return;
+    };
+
+    selection.0 = index;
+
+    let Some(kind) = simulation
+        .buildable()
+        .nth(index)
+        .map(|definition| definition.id.clone())
+    else {
+        return;
+    };
+
+    build_requests.write(BuildRequested {
+        kind,
+        position,
+        facing: GridDirection::default(),
+    });
 }
 
 const SHOP_OFFSET: f32 = 10.;
@@ -144,6 +236,17 @@ struct BuyMenu;
 #[derive(Component)]
 struct BuyMenuOverlay;
 
+/// A pickable entry of the shop, the index of its node type among the
+/// buildable ones
+#[derive(Component)]
+struct ShopItem(pub usize);
+
+#[derive(Component)]
+struct ShopItemCost(pub usize);
+
+#[derive(Component)]
+struct ShopDescription;
+
 fn position_build_menu(
     build_menu_q: Query<&mut Transform, With<BuyMenu>>,
     grid_position: Res<FocusedGridPosition>,
@@ -156,13 +259,33 @@ fn position_build_menu(
     }
 }
 
+fn shop_text_style(font_size: f32) -> SyltTextStyle {
+    SyltTextStyle {
+        font_size,
+        font_axes: SyltFontAxes {
+            weight: Some(900.),
+            ..default()
+        },
+        brush: vello::peniko::Brush::Solid(vello::peniko::Color::WHITE),
+        ..default()
+    }
+}
+
 fn spawn_build_menu(
     mut cmd: Commands,
     show_build_menu: Res<ShowBuildMenu>,
+    mut selection: ResMut<ShopSelection>,
     mut instruction_state: ResMut<NextState<InstructionState>>,
+    simulation: Option<Res<SparkSimulation>>,
+    settings: Res<SyltSettings>,
 ) {
+    let Some(simulation) = simulation else {
+        return;
+    };
+
     if show_build_menu.is_changed() && show_build_menu.0 {
         instruction_state.set(InstructionState::Build);
+        selection.0 = 0;
 
         // spawn overlay
         cmd.spawn((
@@ -183,55 +306,223 @@ fn spawn_build_menu(
             SyltUiScene::default(),
         ))
         .with_children(|parent| {
-            parent.spawn((
-                RenderLayers::layer(1),
-                Transform::from_xyz(CELL_WIDTH / 2., -CELL_HEIGHT - 40., 400.),
-                SyltUiText,
-                SyltText {
-                    content: format!("{STIMULATOR_NODE_COST} SP"),
-                    ..default()
-                },
-                SyltTextAnchor::Center,
-                SyltTextAlign::Middle,
-                SyltTextStyle {
-                    font_size: 20.,
-                    font_axes: SyltFontAxes {
-                        weight: Some(900.),
+            for (index, definition) in simulation.buildable().enumerate() {
+                let offset_x = index as f32 * (CELL_WIDTH + SHOP_OFFSET);
+                let owned = simulation.owned(&definition.id);
+                let price = definition.price(owned);
+                let next_price = definition.price(owned + 1);
+
+                parent
+                    .spawn((
+                        ShopItem(index),
+                        Transform::from_xyz(offset_x, 0., 400.),
+                        SyltPickingShape {
+                            inner: vello::kurbo::Rect::new(
+                                -5.,
+                                -5.,
+                                5. + CELL_WIDTH as f64,
+                                5. + CELL_HEIGHT as f64,
+                            )
+                            .to_path(0.1),
+                            ..default()
+                        },
+                    ))
+                    .observe(on_shop_item_over)
+                    .observe(on_shop_item_release);
+
+                if index < HOTKEYS.len() {
+                    parent.spawn((
+                        RenderLayers::layer(1),
+                        Transform::from_xyz(offset_x + 2., 20., 400.),
+                        SyltUiText,
+                        SyltText {
+                            content: format!("{}", index + 1),
+                            ..default()
+                        },
+                        SyltTextAnchor::Left,
+                        SyltTextAlign::Left,
+                        shop_text_style(16.),
+                        SyltUiUseWorldCoorindates,
+                    ));
+                }
+
+                parent.spawn((
+                    RenderLayers::layer(1),
+                    ShopItemCost(index),
+                    Transform::from_xyz(
+                        offset_x + CELL_WIDTH / 2.,
+                        -CELL_HEIGHT - 40.,
+                        400.,
+                    ),
+                    SyltUiText,
+                    SyltText {
+                        content: format!(
+                            "{} SP",
+                            price.format(settings.number_notation.0)
+                        ),
                         ..default()
                     },
-                    brush: vello::peniko::Brush::Solid(
-                        vello::peniko::Color::WHITE,
+                    SyltTextAnchor::Center,
+                    SyltTextAlign::Middle,
+                    shop_text_style(20.),
+                    SyltUiUseWorldCoorindates,
+                ));
+
+                // Preview of the price after buying this one
+                parent.spawn((
+                    RenderLayers::layer(1),
+                    Transform::from_xyz(
+                        offset_x + CELL_WIDTH / 2.,
+                        -CELL_HEIGHT - 58.,
+                        400.,
                     ),
-                    ..default()
-                },
-                SyltUiUseWorldCoorindates,
-            ));
+                    SyltUiText,
+                    SyltText {
+                        content: format!(
+                            ">> {} SP",
+                            next_price.format(settings.number_notation.0)
+                        ),
+                        ..default()
+                    },
+                    SyltTextAnchor::Center,
+                    SyltTextAlign::Middle,
+                    shop_text_style(14.),
+                    SyltUiUseWorldCoorindates,
+                ));
+
+                parent.spawn((
+                    RenderLayers::layer(1),
+                    Transform::from_xyz(
+                        offset_x + CELL_WIDTH / 2.,
+                        -CELL_HEIGHT - 20.,
+                        400.,
+                    ),
+                    SyltUiText,
+                    SyltText::default(),
+                    SyltTextAnchor::Center,
+                    SyltTextAlign::Middle,
+                    SyltI18nText::from_key(&definition.i18n_key),
+                    shop_text_style(20.),
+                    SyltUiUseWorldCoorindates,
+                ));
+            }
+
+            let description_key = simulation
+                .buildable()
+                .next()
+                .map(|definition| definition.description_key.as_str())
+                .unwrap_or_default();
 
             parent.spawn((
                 RenderLayers::layer(1),
-                Transform::from_xyz(CELL_WIDTH / 2., -CELL_HEIGHT - 20., 400.),
+                ShopDescription,
+                Transform::from_xyz(-5., -CELL_HEIGHT - 85., 400.),
                 SyltUiText,
                 SyltText::default(),
-                SyltTextAnchor::Center,
-                SyltTextAlign::Middle,
-                SyltI18nText::from_key("stimulator node"),
-                SyltTextStyle {
-                    font_size: 20.,
-                    font_axes: SyltFontAxes {
-                        weight: Some(900.),
-                        ..default()
-                    },
-                    brush: vello::peniko::Brush::Solid(
-                        vello::peniko::Color::WHITE,
-                    ),
-                    ..default()
-                },
+                SyltTextAnchor::Left,
+                SyltTextAlign::Left,
+                SyltI18nText::from_key(description_key),
+                shop_text_style(16.),
                 SyltUiUseWorldCoorindates,
             ));
         });
     }
 }
 
+fn on_shop_item_over(
+    trigger: Trigger<Pointer<Over>>,
+    item_q: Query<&ShopItem>,
+    selection: Option<ResMut<ShopSelection>>,
+) {
+    let (Ok(item), Some(mut selection)) =
+        (item_q.get(trigger.target), selection)
+    else {
+        return;
+    };
+
+    if selection.0 != item.0 {
+        selection.0 = item.0;
+    }
+}
+
+fn on_shop_item_release(
+    trigger: Trigger<Pointer<Released>>,
+    item_q: Query<&ShopItem>,
+    focused_grid_position: Option<Res<FocusedGridPosition>>,
+    simulation: Option<Res<SparkSimulation>>,
+    mut build_requests: EventWriter<BuildRequested>,
+) {
+    if trigger.button != PointerButton::Primary {
+        return;
+    }
+
+    let Ok(item) = item_q.get(trigger.target) else {
+        return;
+    };
+
+    let (Some(focused_grid_position), Some(simulation)) =
+        (focused_grid_position, simulation)
+    else {
+        return;
+    };
+
+    let Some(kind) = simulation
+        .buildable()
+        .nth(item.0)
+        .map(|definition| definition.id.clone())
+    else {
+        return;
+    };
+
+    let position = IVec2::new(focused_grid_position.x, focused_grid_position.y);
+
+    build_requests.write(BuildRequested {
+        kind,
+        position,
+        facing: GridDirection::default(),
+    });
+}
+
+fn update_shop_text(
+    selection: Res<ShopSelection>,
+    simulation: Res<SparkSimulation>,
+    cost_q: Query<(&ShopItemCost, &mut SyltTextStyle)>,
+    description_q: Query<&mut SyltI18nText, With<ShopDescription>>,
+) {
+    for (cost, mut style) in cost_q {
+        let Some(definition) = simulation.buildable().nth(cost.0) else {
+            continue;
+        };
+
+        let price = definition.price(simulation.owned(&definition.id));
+
+        let color = if simulation.sparks.0 >= price {
+            vello::peniko::Color::WHITE
+        } else {
+            UNAFFORDABLE_COLOR
+        };
+
+        if style.brush != vello::peniko::Brush::Solid(color) {
+            style.brush = vello::peniko::Brush::Solid(color);
+        }
+    }
+
+    if !selection.is_changed() {
+        return;
+    }
+
+    let Some(definition) = simulation.buildable().nth(selection.0) else {
+        return;
+    };
+
+    for mut description in description_q {
+        description.update_key(&definition.description_key);
+    }
+}
+
+const UNAFFORDABLE_COLOR: vello::peniko::Color =
+    vello::peniko::Color::new([0.9, 0.3, 0.3, 1.]);
+
 fn despawn_build_menu(
     mut cmd: Commands,
     build_menu_q: Query<Entity, With<BuyMenu>>,
@@ -278,37 +569,68 @@ fn draw_overlay(
     }
 }
 
-fn draw_shop(build_menu_q: Query<&mut SyltUiScene, With<BuyMenu>>) {
+fn draw_shop(
+    build_menu_q: Query<&mut SyltUiScene, With<BuyMenu>>,
+    selection: Res<ShopSelection>,
+    simulation: Res<SparkSimulation>,
+) {
     for mut scene in build_menu_q {
         let scene = &mut scene.inner;
 
         scene.reset();
 
-        let background = vello::kurbo::Rect::new(
-            0.,
-            0.,
-            10. + CELL_WIDTH as f64,
-            10. + CELL_HEIGHT as f64,
-        );
-
-        scene.fill(
-            vello::peniko::Fill::NonZero,
-            vello::kurbo::Affine::default()
-                .with_translation(vello::kurbo::Vec2::new(-5., -5.)),
-            vello::peniko::Color::new([1., 1., 1., 0.3]),
-            None,
-            &background,
-        );
-
-        let pentagon =
-            plot_polygon_path((CELL_WIDTH * 0.5, CELL_HEIGHT * 0.5), 40., 5);
+        for (index, definition) in simulation.buildable().enumerate() {
+            let offset = vello::kurbo::Vec2::new(
+                index as f64 * (CELL_WIDTH + SHOP_OFFSET) as f64,
+                0.,
+            );
+
+            let background = vello::kurbo::Rect::new(
+                0.,
+                0.,
+                10. + CELL_WIDTH as f64,
+                10. + CELL_HEIGHT as f64,
+            );
+            let background_transform = vello::kurbo::Affine::default()
+                .with_translation(offset + vello::kurbo::Vec2::new(-5., -5.));
+
+            scene.fill(
+                vello::peniko::Fill::NonZero,
+                background_transform,
+                vello::peniko::Color::new([1., 1., 1., 0.3]),
+                None,
+                &background,
+            );
+
+            if index == selection.0 {
+                scene.stroke(
+                    &vello::kurbo::Stroke::new(4.),
+                    background_transform,
+                    vello::peniko::Color::WHITE,
+                    None,
+                    &background,
+                );
+            }
 
-        scene.fill(
-            vello::peniko::Fill::NonZero,
-            Default::default(),
-            vello::peniko::Color::new([0., 0., 0., 1.]),
-            None,
-            &pentagon,
-        );
+            let shape = definition
+                .shape
+                .to_path((CELL_WIDTH as f64 * 0.5, CELL_HEIGHT as f64 * 0.5));
+
+            // Node types the player can not pay for yet are faded out
+            let price = definition.price(simulation.owned(&definition.id));
+            let alpha = if simulation.sparks.0 >= price {
+                1.
+            } else {
+                0.4
+            };
+
+            scene.fill(
+                vello::peniko::Fill::NonZero,
+                vello::kurbo::Affine::default().with_translation(offset),
+                definition.color().with_alpha(alpha),
+                None,
+                &shape,
+            );
+        }
     }
 }
@@ -0,0 +1,763 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use thiserror::Error;
+use vello::kurbo::Shape;
+
+use crate::{
+    game::{
+        currency::SparkAmount, prestige::PrestigeBonusDefinition,
+        pulses::PULSE_HOP_DELAY, upgrades::UpgradeDefinition,
+    },
+    vectors::polygon::plot_polygon_path,
+};
+
+pub struct CatalogPlugin;
+
+impl Plugin for CatalogPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<NodeCatalogFile>()
+            .init_asset_loader::<NodeCatalogAssetLoader>()
+            .init_resource::<NodeCatalog>()
+            .add_systems(Startup, load_node_catalog)
+            .add_systems(Update, set_node_catalog_on_load);
+    }
+}
+
+/// A spark node type, as described in `assets/nodes.ron`.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct NodeDefinition {
+    pub id: String,
+    pub i18n_key: String,
+    /// Shown in the build menu
+    pub description_key: String,
+    /// Price of the first node of this type
+    pub cost: SparkAmount,
+    /// How the price grows with every node of this type already owned
+    #[serde(default)]
+    pub cost_curve: CostCurve,
+    /// Share of the price returned when selling a node of this type
+    #[serde(default = "default_refund_percentage")]
+    pub refund_percentage: f64,
+    /// Sparks generated every time the node is triggered
+    pub yields: SparkAmount,
+    /// Seconds between pulses, nodes without an interval never pulse on their own
+    pub pulse_interval: Option<f32>,
+    /// Seconds pulses sent by this node take to arrive, defaults to
+    /// [`PULSE_HOP_DELAY`]
+    #[serde(default)]
+    pub pulse_delay: Option<f32>,
+    pub trigger_pattern: TriggerPattern,
+    pub shape: NodeShape,
+    pub color: [f32; 4],
+    pub buildable: bool,
+}
+
+fn default_refund_percentage() -> f64 {
+    50.
+}
+
+impl NodeDefinition {
+    pub fn color(&self) -> vello::peniko::Color {
+        vello::peniko::Color::new(self.color)
+    }
+
+    pub fn pulse_delay(&self) -> f32 {
+        self.pulse_delay.unwrap_or(PULSE_HOP_DELAY).max(0.)
+    }
+
+    /// Price of the next node of this type when `owned` are already placed
+    pub fn price(&self, owned: usize) -> SparkAmount {
+        self.cost_curve.apply(self.cost, owned)
+    }
+}
+
+/// Scales the base cost of a node type by the number already owned
+#[derive(
+    Debug, Clone, Default, PartialEq, serde::Deserialize, serde::Serialize,
+)]
+pub enum CostCurve {
+    /// Every node costs the base cost
+    #[default]
+    Flat,
+    /// `cost * (1 + rate * owned)`
+    Linear(f64),
+    /// `cost * growth ^ owned`
+    Exponential(f64),
+    /// `cost * (c0 + c1 * owned + c2 * owned ^ 2 + ...)`
+    Polynomial(Vec<f64>),
+}
+
+impl CostCurve {
+    /// Whether the factor stays positive for every number of owned nodes, a
+    /// curve that reaches zero would make nodes free
+    pub fn is_valid(&self) -> bool {
+        match self {
+            CostCurve::Flat => true,
+            CostCurve::Linear(rate) => rate.is_finite() && *rate >= 0.,
+            CostCurve::Exponential(growth) => {
+                growth.is_finite() && *growth > 0.
+            }
+            CostCurve::Polynomial(coefficients) => {
+                coefficients.first().is_some_and(|first| *first > 0.)
+                    && coefficients.iter().all(|coefficient| {
+                        coefficient.is_finite() && *coefficient >= 0.
+                    })
+            }
+        }
+    }
+
+    pub fn apply(&self, cost: SparkAmount, owned: usize) -> SparkAmount {
+        let owned = owned as f64;
+
+        let factor = match self {
+            CostCurve::Flat => return cost,
+            // Computed in log space, the factor outgrows an f64 quickly
+            CostCurve::Exponential(growth) => {
+                return SparkAmount::from_log10(
+                    cost.log10() + owned * growth.log10(),
+                );
+            }
+            CostCurve::Linear(rate) => 1. + rate * owned,
+            CostCurve::Polynomial(coefficients) => coefficients
+                .iter()
+                .rev()
+                .fold(0., |sum, coefficient| sum * owned + coefficient),
+        };
+
+        cost.checked_mul(SparkAmount::from(factor))
+            .unwrap_or(SparkAmount::MAX)
+    }
+}
+
+/// One of the eight directions on the grid, rows grow southwards
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, serde::Deserialize, serde::Serialize,
+)]
+pub enum GridDirection {
+    #[default]
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl GridDirection {
+    /// Every direction, clockwise from north
+    pub const ALL: [GridDirection; 8] = [
+        GridDirection::North,
+        GridDirection::NorthEast,
+        GridDirection::East,
+        GridDirection::SouthEast,
+        GridDirection::South,
+        GridDirection::SouthWest,
+        GridDirection::West,
+        GridDirection::NorthWest,
+    ];
+
+    pub fn offset(&self) -> IVec2 {
+        match self {
+            GridDirection::North => IVec2::new(0, -1),
+            GridDirection::NorthEast => IVec2::new(1, -1),
+            GridDirection::East => IVec2::new(1, 0),
+            GridDirection::SouthEast => IVec2::new(1, 1),
+            GridDirection::South => IVec2::new(0, 1),
+            GridDirection::SouthWest => IVec2::new(-1, 1),
+            GridDirection::West => IVec2::new(-1, 0),
+            GridDirection::NorthWest => IVec2::new(-1, -1),
+        }
+    }
+
+    /// The next direction, an eighth of a turn away
+    pub fn rotated(&self, clockwise: bool) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|direction| direction == self)
+            .unwrap_or_default();
+        let step = if clockwise { 1 } else { Self::ALL.len() - 1 };
+
+        Self::ALL[(index + step) % Self::ALL.len()]
+    }
+}
+
+/// Which cells relative to a pulsing node get triggered
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub enum TriggerPattern {
+    #[default]
+    None,
+    /// The 8 surrounding cells, same as `Moore(1)`
+    Neighbours,
+    /// The 4 cells sharing an edge
+    Orthogonal,
+    /// The 4 cells sharing only a corner
+    Diagonal,
+    /// Every cell up to a number of cells away, diagonals included
+    Moore(i32),
+    /// The first node found walking up to `range` cells in a direction
+    LineOfSight {
+        direction: GridDirection,
+        range: i32,
+    },
+    /// Like `LineOfSight`, in the direction each node is facing
+    Facing { range: i32 },
+    /// Cells a knight move away, as in chess
+    Knight,
+    /// Any list of `(x, y)` offsets
+    Custom(Vec<(i32, i32)>),
+}
+
+impl TriggerPattern {
+    /// Whether nodes with this pattern can be turned to face elsewhere
+    pub fn is_directional(&self) -> bool {
+        matches!(self, TriggerPattern::Facing { .. })
+    }
+
+    /// Occupied cells a node at `source` facing `facing` triggers. `reach`
+    /// is the number of extra cells upgrades add, patterns with a fixed
+    /// shape ignore it. Only cells of the pattern are checked, never the
+    /// whole board.
+    pub fn targets(
+        &self,
+        source: IVec2,
+        facing: GridDirection,
+        reach: i32,
+        is_occupied: impl Fn(IVec2) -> bool,
+    ) -> Vec<IVec2> {
+        let reach = reach.max(0);
+
+        let line = match self {
+            TriggerPattern::LineOfSight { direction, range } => {
+                Some((*direction, *range))
+            }
+            TriggerPattern::Facing { range } => Some((facing, *range)),
+            _ => None,
+        };
+
+        if let Some((direction, range)) = line {
+            return (1..=range + reach)
+                .map(|step| source + direction.offset() * step)
+                .find(|cell| is_occupied(*cell))
+                .into_iter()
+                .collect();
+        }
+
+        self.offsets(facing, reach)
+            .into_iter()
+            .map(|offset| source + offset)
+            .filter(|cell| is_occupied(*cell))
+            .collect()
+    }
+
+    /// Cells the pattern covers relative to the node, widened by `reach`
+    pub fn offsets(&self, facing: GridDirection, reach: i32) -> Vec<IVec2> {
+        match self {
+            TriggerPattern::None => Vec::new(),
+            TriggerPattern::Neighbours => moore_offsets(1 + reach),
+            TriggerPattern::Moore(radius) => moore_offsets(radius + reach),
+            TriggerPattern::Orthogonal => {
+                rays(&[(0, -1), (1, 0), (0, 1), (-1, 0)], 1 + reach)
+            }
+            TriggerPattern::Diagonal => {
+                rays(&[(1, -1), (1, 1), (-1, 1), (-1, -1)], 1 + reach)
+            }
+            TriggerPattern::LineOfSight { direction, range } => {
+                rays(&[direction.offset().into()], range + reach)
+            }
+            TriggerPattern::Facing { range } => {
+                rays(&[facing.offset().into()], range + reach)
+            }
+            TriggerPattern::Knight => [
+                (1, -2),
+                (2, -1),
+                (2, 1),
+                (1, 2),
+                (-1, 2),
+                (-2, 1),
+                (-2, -1),
+                (-1, -2),
+            ]
+            .into_iter()
+            .map(IVec2::from)
+            .collect(),
+            TriggerPattern::Custom(offsets) => offsets
+                .iter()
+                .map(|offset| IVec2::from(*offset))
+                .filter(|offset| *offset != IVec2::ZERO)
+                .collect(),
+        }
+    }
+}
+
+/// The square of cells around the origin, without the origin itself
+fn moore_offsets(radius: i32) -> Vec<IVec2> {
+    (-radius..=radius)
+        .flat_map(|y| (-radius..=radius).map(move |x| IVec2::new(x, y)))
+        .filter(|offset| *offset != IVec2::ZERO)
+        .collect()
+}
+
+/// Cells along each direction, up to `length` cells away
+fn rays(directions: &[(i32, i32)], length: i32) -> Vec<IVec2> {
+    directions
+        .iter()
+        .flat_map(|direction| {
+            (1..=length).map(move |step| IVec2::from(*direction) * step)
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub enum NodeShape {
+    Circle { radius: f64 },
+    Polygon { radius: f64, sides: usize },
+}
+
+impl NodeShape {
+    pub fn to_path(
+        &self,
+        center: impl Into<vello::kurbo::Point>,
+    ) -> vello::kurbo::BezPath {
+        match self {
+            NodeShape::Circle { radius } => {
+                vello::kurbo::Circle::new(center, *radius).to_path(0.1)
+            }
+            NodeShape::Polygon { radius, sides } => {
+                plot_polygon_path(center, *radius, *sides)
+            }
+        }
+    }
+}
+
+/// The loaded node definitions, kept in sync with the catalog asset.
+#[derive(Resource, Default)]
+pub struct NodeCatalog {
+    pub nodes: Vec<NodeDefinition>,
+    pub upgrades: Vec<UpgradeDefinition>,
+    pub prestige_bonuses: Vec<PrestigeBonusDefinition>,
+}
+
+impl NodeCatalog {
+    pub fn get(&self, id: &str) -> Option<&NodeDefinition> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    pub fn buildable(&self) -> impl Iterator<Item = &NodeDefinition> {
+        self.nodes.iter().filter(|node| node.buildable)
+    }
+}
+
+#[derive(Resource)]
+pub struct NodeCatalogAssets {
+    pub catalog: Handle<NodeCatalogFile>,
+}
+
+fn load_node_catalog(mut cmd: Commands, asset_server: Res<AssetServer>) {
+    cmd.insert_resource(NodeCatalogAssets {
+        catalog: asset_server.load("nodes.ron"),
+    });
+}
+
+fn set_node_catalog_on_load(
+    mut asset_events: EventReader<AssetEvent<NodeCatalogFile>>,
+    node_catalog_assets: Res<NodeCatalogAssets>,
+    node_catalog_files: Res<Assets<NodeCatalogFile>>,
+    mut node_catalog: ResMut<NodeCatalog>,
+) {
+    for event in asset_events.read() {
+        match event {
+            AssetEvent::LoadedWithDependencies { id }
+            | AssetEvent::Modified { id } => {
+                if *id != node_catalog_assets.catalog.id() {
+                    continue;
+                }
+
+                if let Some(file) =
+                    node_catalog_files.get(&node_catalog_assets.catalog)
+                {
+                    node_catalog.nodes = file.nodes.clone();
+                    node_catalog.upgrades = file.upgrades.clone();
+                    node_catalog.prestige_bonuses =
+                        file.prestige_bonuses.clone();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Asset, TypePath, serde::Deserialize, serde::Serialize)]
+pub struct NodeCatalogFile {
+    pub nodes: Vec<NodeDefinition>,
+    #[serde(default)]
+    pub upgrades: Vec<UpgradeDefinition>,
+    #[serde(default)]
+    pub prestige_bonuses: Vec<PrestigeBonusDefinition>,
+}
+
+impl NodeCatalogFile {
+    pub fn validate(&self) -> Result<(), NodeCatalogAssetLoaderError> {
+        match self.nodes.iter().find(|node| !node.cost_curve.is_valid()) {
+            Some(node) => Err(NodeCatalogAssetLoaderError::InvalidCostCurve(
+                node.id.clone(),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct NodeCatalogAssetLoader;
+
+impl AssetLoader for NodeCatalogAssetLoader {
+    type Asset = NodeCatalogFile;
+    type Settings = ();
+    type Error = NodeCatalogAssetLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let asset = ron::de::from_bytes::<Self::Asset>(&bytes)?;
+        asset.validate()?;
+        Ok(asset)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum NodeCatalogAssetLoaderError {
+    #[error("Could not read the file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse RON: {0}")]
+    RonError(#[from] ron::error::SpannedError),
+    #[error("Node {0} has a cost curve that reaches zero")]
+    InvalidCostCurve(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Prices of the first five nodes of a type costing 10 sparks
+    fn price_table(curve: CostCurve) -> Vec<f64> {
+        (0..5)
+            .map(|owned| curve.apply(SparkAmount::from(10.), owned).to_f64())
+            .collect()
+    }
+
+    fn assert_prices(actual: Vec<f64>, expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert!(
+                (actual - expected).abs() <= expected * 1e-9,
+                "{actual} is not {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn flat_prices() {
+        assert_prices(price_table(CostCurve::Flat), &[10., 10., 10., 10., 10.]);
+    }
+
+    #[test]
+    fn linear_prices() {
+        assert_prices(
+            price_table(CostCurve::Linear(0.5)),
+            &[10., 15., 20., 25., 30.],
+        );
+    }
+
+    #[test]
+    fn exponential_prices() {
+        assert_prices(
+            price_table(CostCurve::Exponential(2.)),
+            &[10., 20., 40., 80., 160.],
+        );
+        assert_prices(
+            price_table(CostCurve::Exponential(1.15)),
+            &[10., 11.5, 13.225, 15.20875, 17.490_062_5],
+        );
+    }
+
+    #[test]
+    fn exponential_prices_beyond_f64() {
+        let price =
+            CostCurve::Exponential(10.).apply(SparkAmount::from(10.), 400);
+
+        assert!((price.log10() - 401.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn polynomial_prices() {
+        // 1 + owned ^ 2
+        assert_prices(
+            price_table(CostCurve::Polynomial(vec![1., 0., 1.])),
+            &[10., 20., 50., 100., 170.],
+        );
+        assert_prices(
+            price_table(CostCurve::Polynomial(Vec::new())),
+            &[0., 0., 0., 0., 0.],
+        );
+    }
+
+    #[test]
+    fn prices_are_never_negative() {
+        assert_eq!(
+            CostCurve::Linear(-1.).apply(SparkAmount::from(10.), 2),
+            SparkAmount::ZERO
+        );
+    }
+
+    #[test]
+    fn curves_that_reach_zero_are_invalid() {
+        assert!(CostCurve::Flat.is_valid());
+        assert!(CostCurve::Linear(0.).is_valid());
+        assert!(CostCurve::Exponential(0.5).is_valid());
+        assert!(CostCurve::Polynomial(vec![1., 0., 1.]).is_valid());
+
+        assert!(!CostCurve::Linear(-0.1).is_valid());
+        assert!(!CostCurve::Linear(f64::NAN).is_valid());
+        assert!(!CostCurve::Exponential(0.).is_valid());
+        assert!(!CostCurve::Exponential(-2.).is_valid());
+        assert!(!CostCurve::Exponential(f64::INFINITY).is_valid());
+        assert!(!CostCurve::Polynomial(Vec::new()).is_valid());
+        assert!(!CostCurve::Polynomial(vec![0., 1.]).is_valid());
+        assert!(!CostCurve::Polynomial(vec![1., -1.]).is_valid());
+    }
+
+    #[test]
+    fn catalogs_with_invalid_curves_are_rejected() {
+        let catalog = ron::de::from_str::<NodeCatalogFile>(
+            &std::fs::read_to_string("assets/nodes.ron").unwrap(),
+        )
+        .unwrap();
+        assert!(catalog.validate().is_ok());
+
+        let mut catalog = catalog;
+        catalog.nodes[0].cost_curve = CostCurve::Exponential(0.);
+        let id = catalog.nodes[0].id.clone();
+        assert!(matches!(
+            catalog.validate(),
+            Err(NodeCatalogAssetLoaderError::InvalidCostCurve(invalid))
+                if invalid == id
+        ));
+    }
+
+    /// Every cell of a 5x5 board holds a node
+    fn on_full_board(
+        pattern: &TriggerPattern,
+        source: (i32, i32),
+        facing: GridDirection,
+        reach: i32,
+    ) -> Vec<(i32, i32)> {
+        on_board(pattern, source, facing, reach, |cell| {
+            (0..5).contains(&cell.x) && (0..5).contains(&cell.y)
+        })
+    }
+
+    fn on_board(
+        pattern: &TriggerPattern,
+        source: (i32, i32),
+        facing: GridDirection,
+        reach: i32,
+        is_occupied: impl Fn(IVec2) -> bool,
+    ) -> Vec<(i32, i32)> {
+        let mut targets: Vec<(i32, i32)> = pattern
+            .targets(IVec2::from(source), facing, reach, is_occupied)
+            .into_iter()
+            .map(|cell| (cell.x, cell.y))
+            .collect();
+        targets.sort_by_key(|(x, y)| (*y, *x));
+        targets
+    }
+
+    const NORTH: GridDirection = GridDirection::North;
+
+    #[test]
+    fn no_pattern_triggers_nothing() {
+        assert!(
+            on_full_board(&TriggerPattern::None, (2, 2), NORTH, 3).is_empty()
+        );
+    }
+
+    #[test]
+    fn neighbours() {
+        let pattern = TriggerPattern::Neighbours;
+
+        assert_eq!(on_full_board(&pattern, (2, 2), NORTH, 0).len(), 8);
+        assert_eq!(
+            on_full_board(&pattern, (0, 0), NORTH, 0),
+            vec![(1, 0), (0, 1), (1, 1)]
+        );
+        assert_eq!(on_full_board(&pattern, (2, 2), NORTH, 1).len(), 24);
+        // Negative reach does not shrink the pattern
+        assert_eq!(on_full_board(&pattern, (2, 2), NORTH, -3).len(), 8);
+    }
+
+    #[test]
+    fn orthogonal_and_diagonal() {
+        assert_eq!(
+            on_full_board(&TriggerPattern::Orthogonal, (0, 0), NORTH, 0),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(
+            on_full_board(&TriggerPattern::Orthogonal, (2, 2), NORTH, 1),
+            vec![
+                (2, 0),
+                (2, 1),
+                (0, 2),
+                (1, 2),
+                (3, 2),
+                (4, 2),
+                (2, 3),
+                (2, 4)
+            ]
+        );
+        assert_eq!(
+            on_full_board(&TriggerPattern::Diagonal, (4, 4), NORTH, 0),
+            vec![(3, 3)]
+        );
+    }
+
+    #[test]
+    fn moore() {
+        let pattern = TriggerPattern::Moore(2);
+
+        assert_eq!(on_full_board(&pattern, (2, 2), NORTH, 0).len(), 24);
+        assert_eq!(on_full_board(&pattern, (0, 0), NORTH, 0).len(), 8);
+    }
+
+    #[test]
+    fn knight() {
+        let pattern = TriggerPattern::Knight;
+
+        assert_eq!(
+            on_full_board(&pattern, (2, 2), NORTH, 0),
+            vec![
+                (1, 0),
+                (3, 0),
+                (0, 1),
+                (4, 1),
+                (0, 3),
+                (4, 3),
+                (1, 4),
+                (3, 4)
+            ]
+        );
+        assert_eq!(
+            on_full_board(&pattern, (0, 0), NORTH, 0),
+            vec![(2, 1), (1, 2)]
+        );
+        // The fixed shape ignores reach
+        assert_eq!(
+            on_full_board(&pattern, (0, 0), NORTH, 2),
+            vec![(2, 1), (1, 2)]
+        );
+    }
+
+    #[test]
+    fn line_of_sight_stops_at_the_first_node() {
+        let pattern = TriggerPattern::LineOfSight {
+            direction: GridDirection::East,
+            range: 3,
+        };
+        let row = |cell: IVec2| cell.y == 2 && [2, 3].contains(&cell.x);
+
+        assert_eq!(on_board(&pattern, (0, 2), NORTH, 0, row), vec![(2, 2)]);
+    }
+
+    #[test]
+    fn line_of_sight_range() {
+        let pattern = TriggerPattern::LineOfSight {
+            direction: GridDirection::East,
+            range: 3,
+        };
+        let far = |cell: IVec2| cell == IVec2::new(4, 2);
+
+        assert!(on_board(&pattern, (0, 2), NORTH, 0, far).is_empty());
+        assert_eq!(on_board(&pattern, (0, 2), NORTH, 1, far), vec![(4, 2)]);
+    }
+
+    #[test]
+    fn line_of_sight_at_the_edge() {
+        let pattern = TriggerPattern::LineOfSight {
+            direction: GridDirection::East,
+            range: 3,
+        };
+
+        assert!(on_full_board(&pattern, (4, 2), NORTH, 0).is_empty());
+        // The facing of the node does not matter
+        assert_eq!(
+            on_full_board(&pattern, (3, 2), GridDirection::West, 0),
+            vec![(4, 2)]
+        );
+    }
+
+    #[test]
+    fn facing_follows_the_node() {
+        let pattern = TriggerPattern::Facing { range: 2 };
+
+        assert_eq!(
+            on_full_board(&pattern, (2, 3), GridDirection::South, 0),
+            vec![(2, 4)]
+        );
+        assert_eq!(
+            on_full_board(&pattern, (1, 1), GridDirection::NorthWest, 0),
+            vec![(0, 0)]
+        );
+        assert_eq!(
+            on_full_board(&pattern, (2, 2), GridDirection::East, 0),
+            vec![(3, 2)]
+        );
+    }
+
+    #[test]
+    fn facing_the_edge() {
+        let pattern = TriggerPattern::Facing { range: 2 };
+
+        assert!(
+            on_full_board(&pattern, (2, 4), GridDirection::South, 0).is_empty()
+        );
+        assert!(on_full_board(&pattern, (0, 0), GridDirection::NorthEast, 4)
+            .is_empty());
+    }
+
+    #[test]
+    fn custom_offsets() {
+        let pattern = TriggerPattern::Custom(vec![(0, 0), (0, -1), (9, 9)]);
+
+        // The node itself and cells off the board are never targets
+        assert_eq!(on_full_board(&pattern, (2, 2), NORTH, 0), vec![(2, 1)]);
+    }
+
+    #[test]
+    fn rotation() {
+        assert_eq!(
+            GridDirection::North.rotated(true),
+            GridDirection::NorthEast
+        );
+        assert_eq!(
+            GridDirection::North.rotated(false),
+            GridDirection::NorthWest
+        );
+        assert_eq!(
+            GridDirection::NorthWest.rotated(true),
+            GridDirection::North
+        );
+    }
+}
@@ -0,0 +1,490 @@
+use std::{cmp::Ordering, fmt, ops::AddAssign};
+
+use serde::{Deserialize, Serialize};
+
+/// Short suffixes for every power of 1000, larger amounts fall back to
+/// scientific notation
+const SHORT_SUFFIXES: [&str; 11] =
+    ["", "K", "M", "B", "T", "Qa", "Qi", "Sx", "Sp", "Oc", "No"];
+
+/// Non-negative amount of sparks stored as `mantissa * 10^exponent`, with
+/// the mantissa normalized to `[1, 10)`, or zero.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(from = "SparkAmountRepr")]
+pub struct SparkAmount {
+    mantissa: f64,
+    exponent: i64,
+}
+
+/// Amounts can be written as plain numbers, e.g. in `assets/nodes.ron`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SparkAmountRepr {
+    Number(f64),
+    Parts { mantissa: f64, exponent: i64 },
+}
+
+impl From<SparkAmountRepr> for SparkAmount {
+    fn from(repr: SparkAmountRepr) -> Self {
+        match repr {
+            SparkAmountRepr::Number(value) => Self::from(value),
+            SparkAmountRepr::Parts { mantissa, exponent } => {
+                Self::normalized(mantissa, exponent)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum NumberNotation {
+    /// 1.23K, 4.56M, 7.89B...
+    #[default]
+    Short,
+    /// 1.23e4
+    Scientific,
+    /// 12.3e3, exponents are multiples of three
+    Engineering,
+}
+
+impl SparkAmount {
+    pub const ZERO: Self = Self {
+        mantissa: 0.,
+        exponent: 0,
+    };
+
+    pub const MAX: Self = Self {
+        mantissa: 9.999_999_999,
+        exponent: i64::MAX,
+    };
+
+    fn normalized(mantissa: f64, exponent: i64) -> Self {
+        if !mantissa.is_finite() || mantissa <= 0. {
+            return Self::ZERO;
+        }
+
+        let mut shift = mantissa.log10().floor() as i64;
+        let mut mantissa = mantissa / 10f64.powi(shift as i32);
+
+        // Drop floating point noise so 1e300 is not stored as 9.99..e299,
+        // only a few ULPs away so no real precision is lost
+        let snapped = (mantissa * 1e14).round() / 1e14;
+        if (snapped - mantissa).abs() <= 4. * f64::EPSILON * mantissa {
+            mantissa = snapped;
+        }
+
+        // log10 can be off by one close to powers of ten
+        if mantissa >= 10. {
+            mantissa /= 10.;
+            shift += 1;
+        } else if mantissa < 1. {
+            mantissa *= 10.;
+            shift -= 1;
+        }
+
+        match exponent.checked_add(shift) {
+            Some(exponent) => Self { mantissa, exponent },
+            None if shift > 0 => Self::MAX,
+            None => Self::ZERO,
+        }
+    }
+
+    /// Amount from its base 10 logarithm, for values that would overflow an
+    /// `f64` before normalizing
+    pub fn from_log10(value: f64) -> Self {
+        if value.is_nan() || value == f64::NEG_INFINITY {
+            return Self::ZERO;
+        }
+
+        if value >= i64::MAX as f64 {
+            return Self::MAX;
+        }
+
+        let exponent = value.floor();
+        Self::normalized(10f64.powf(value - exponent), exponent as i64)
+    }
+
+    /// Base 10 logarithm, negative infinity for zero
+    pub fn log10(&self) -> f64 {
+        self.mantissa.log10() + self.exponent as f64
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0.
+    }
+
+    /// Lossy conversion, amounts beyond `f64::MAX` become infinite
+    pub fn to_f64(&self) -> f64 {
+        self.mantissa * 10f64.powf(self.exponent as f64)
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        if self.is_zero() {
+            return Some(other);
+        }
+
+        if other.is_zero() {
+            return Some(self);
+        }
+
+        let (larger, smaller) = if self >= other {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
+        // The smaller amount is below the precision of the larger one
+        let difference = larger.exponent - smaller.exponent;
+        if difference > 17 {
+            return Some(larger);
+        }
+
+        let mantissa =
+            larger.mantissa + smaller.mantissa / 10f64.powi(difference as i32);
+
+        if mantissa >= 10. && larger.exponent == i64::MAX {
+            return None;
+        }
+
+        Some(Self::normalized(mantissa, larger.exponent))
+    }
+
+    /// Returns `None` if the result would be negative
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        if other.is_zero() {
+            return Some(self);
+        }
+
+        if self < other {
+            return None;
+        }
+
+        let difference = self.exponent - other.exponent;
+        if difference > 17 {
+            return Some(self);
+        }
+
+        let mantissa =
+            self.mantissa - other.mantissa / 10f64.powi(difference as i32);
+
+        // Rounding errors should not leave tiny fractions of a spark
+        if mantissa < 1e-12 {
+            return Some(Self::ZERO);
+        }
+
+        Some(Self::normalized(mantissa, self.exponent))
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        if self.is_zero() || other.is_zero() {
+            return Some(Self::ZERO);
+        }
+
+        let exponent = self.exponent.checked_add(other.exponent)?;
+        let mantissa = self.mantissa * other.mantissa;
+
+        if mantissa >= 10. && exponent == i64::MAX {
+            return None;
+        }
+
+        Some(Self::normalized(mantissa, exponent))
+    }
+
+    pub fn format(&self, notation: NumberNotation) -> String {
+        if self.is_zero() {
+            return "0".to_string();
+        }
+
+        // Small amounts are shown as whole sparks in every notation
+        if self.exponent < 3 {
+            return format!("{}", self.to_f64().floor());
+        }
+
+        match notation {
+            NumberNotation::Short => {
+                let group = (self.exponent / 3) as usize;
+
+                match SHORT_SUFFIXES.get(group) {
+                    Some(suffix) => {
+                        let value = self.mantissa
+                            * 10f64.powi((self.exponent % 3) as i32);
+                        format!("{}{suffix}", trim_decimals(value))
+                    }
+                    None => self.format(NumberNotation::Scientific),
+                }
+            }
+            NumberNotation::Scientific => {
+                format!("{}e{}", trim_decimals(self.mantissa), self.exponent)
+            }
+            NumberNotation::Engineering => {
+                let exponent = self.exponent - self.exponent.rem_euclid(3);
+                let value = self.mantissa
+                    * 10f64.powi((self.exponent - exponent) as i32);
+                format!("{}e{exponent}", trim_decimals(value))
+            }
+        }
+    }
+
+    /// Like [`SparkAmount::format`], but small amounts keep two decimals so
+    /// rates below one spark per second do not show up as zero
+    pub fn format_rate(&self, notation: NumberNotation) -> String {
+        if !self.is_zero() && self.exponent < 3 {
+            return trim_decimals(self.to_f64());
+        }
+
+        self.format(notation)
+    }
+}
+
+/// Two decimals at most, without trailing zeroes, rounded down so an amount
+/// is never shown larger than it is
+fn trim_decimals(value: f64) -> String {
+    let truncated = (value * 100.).floor() / 100.;
+    let formatted = format!("{truncated:.2}");
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+impl From<f64> for SparkAmount {
+    fn from(value: f64) -> Self {
+        Self::normalized(value, 0)
+    }
+}
+
+impl From<f32> for SparkAmount {
+    fn from(value: f32) -> Self {
+        Self::from(value as f64)
+    }
+}
+
+impl From<u64> for SparkAmount {
+    fn from(value: u64) -> Self {
+        Self::from(value as f64)
+    }
+}
+
+impl PartialOrd for SparkAmount {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self.is_zero(), other.is_zero()) {
+            (true, true) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (false, false) => match self.exponent.cmp(&other.exponent) {
+                Ordering::Equal => self.mantissa.partial_cmp(&other.mantissa),
+                ordering => Some(ordering),
+            },
+        }
+    }
+}
+
+/// Saturates at [`SparkAmount::MAX`]
+impl AddAssign for SparkAmount {
+    fn add_assign(&mut self, other: Self) {
+        *self = self.checked_add(other).unwrap_or(Self::MAX);
+    }
+}
+
+impl fmt::Display for SparkAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.format(NumberNotation::Short))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts(amount: SparkAmount) -> (f64, i64) {
+        (amount.mantissa, amount.exponent)
+    }
+
+    #[test]
+    fn normalizes_the_mantissa() {
+        assert_eq!(parts(SparkAmount::from(1234.)), (1.234, 3));
+        assert_eq!(parts(SparkAmount::from(0.05)), (5., -2));
+        assert_eq!(parts(SparkAmount::from(1e300)), (1., 300));
+        assert_eq!(parts(SparkAmount::normalized(123., 1_000)), (1.23, 1_002));
+    }
+
+    #[test]
+    fn invalid_values_are_zero() {
+        assert_eq!(SparkAmount::from(0.), SparkAmount::ZERO);
+        assert_eq!(SparkAmount::from(-5.), SparkAmount::ZERO);
+        assert_eq!(SparkAmount::from(f64::NAN), SparkAmount::ZERO);
+        assert_eq!(SparkAmount::from(f64::INFINITY), SparkAmount::ZERO);
+        assert_eq!(
+            SparkAmount::from_log10(f64::NEG_INFINITY),
+            SparkAmount::ZERO
+        );
+    }
+
+    #[test]
+    fn addition_carries_into_the_exponent() {
+        let sum = SparkAmount::from(999.)
+            .checked_add(SparkAmount::from(1.))
+            .unwrap();
+        assert_eq!(parts(sum), (1., 3));
+
+        let sum = SparkAmount::from(9.5)
+            .checked_add(SparkAmount::from(0.6))
+            .unwrap();
+        assert_eq!(parts(sum), (1.01, 1));
+    }
+
+    #[test]
+    fn addition_across_exponents() {
+        let sum = SparkAmount::from(1e6)
+            .checked_add(SparkAmount::from(1e3))
+            .unwrap();
+        assert_eq!(parts(sum), (1.001, 6));
+
+        // Far below the precision of the larger amount
+        let sum = SparkAmount::from(1e30)
+            .checked_add(SparkAmount::from(1.))
+            .unwrap();
+        assert_eq!(sum, SparkAmount::from(1e30));
+    }
+
+    #[test]
+    fn addition_keeps_small_increments() {
+        let balance = SparkAmount::from(12_345_678_901_234.);
+        let sum = balance.checked_add(SparkAmount::from(1.)).unwrap();
+        assert!(sum > balance);
+        assert_eq!(sum.to_f64().round(), 12_345_678_901_235.);
+
+        let mut total = balance;
+        for _ in 0..1_000 {
+            total += SparkAmount::from(0.5);
+        }
+        assert_eq!(total.to_f64().round(), 12_345_678_901_734.);
+    }
+
+    #[test]
+    fn addition_saturates() {
+        assert_eq!(SparkAmount::MAX.checked_add(SparkAmount::MAX), None);
+
+        let mut amount = SparkAmount::MAX;
+        amount += SparkAmount::MAX;
+        assert_eq!(amount, SparkAmount::MAX);
+    }
+
+    #[test]
+    fn subtraction_borrows_from_the_exponent() {
+        let difference = SparkAmount::from(1000.)
+            .checked_sub(SparkAmount::from(1.))
+            .unwrap();
+        assert_eq!(parts(difference), (9.99, 2));
+    }
+
+    #[test]
+    fn subtraction_does_not_underflow() {
+        assert_eq!(
+            SparkAmount::from(5.).checked_sub(SparkAmount::from(6.)),
+            None
+        );
+        assert_eq!(SparkAmount::ZERO.checked_sub(SparkAmount::from(1.)), None);
+        assert_eq!(
+            SparkAmount::from(5.).checked_sub(SparkAmount::from(5.)),
+            Some(SparkAmount::ZERO)
+        );
+    }
+
+    #[test]
+    fn multiplication_overflows_to_none() {
+        let product = SparkAmount::from(2e3)
+            .checked_mul(SparkAmount::from(3e4))
+            .unwrap();
+        assert_eq!(parts(product), (6., 7));
+
+        assert_eq!(SparkAmount::MAX.checked_mul(SparkAmount::from(10.)), None);
+        assert_eq!(
+            SparkAmount::MAX.checked_mul(SparkAmount::ZERO),
+            Some(SparkAmount::ZERO)
+        );
+    }
+
+    #[test]
+    fn ordering() {
+        assert!(SparkAmount::ZERO < SparkAmount::from(0.001));
+        assert!(SparkAmount::from(9e5) < SparkAmount::from(1e6));
+        assert!(SparkAmount::from(2e6) > SparkAmount::from(1e6));
+    }
+
+    fn format(value: f64, notation: NumberNotation) -> String {
+        SparkAmount::from(value).format(notation)
+    }
+
+    #[test]
+    fn small_amounts_are_whole_sparks() {
+        for notation in [
+            NumberNotation::Short,
+            NumberNotation::Scientific,
+            NumberNotation::Engineering,
+        ] {
+            assert_eq!(format(0., notation), "0");
+            assert_eq!(format(0.5, notation), "0");
+            assert_eq!(format(999.9, notation), "999");
+        }
+    }
+
+    #[test]
+    fn small_rates_keep_decimals() {
+        let short = NumberNotation::Short;
+        let rate = |value: f64| SparkAmount::from(value).format_rate(short);
+
+        assert_eq!(rate(0.), "0");
+        assert_eq!(rate(0.4), "0.4");
+        assert_eq!(rate(2.7), "2.7");
+        assert_eq!(rate(0.125), "0.12");
+        assert_eq!(rate(999.999), "999.99");
+        assert_eq!(rate(1500.), "1.5K");
+    }
+
+    #[test]
+    fn short_notation() {
+        let short = NumberNotation::Short;
+
+        assert_eq!(format(1000., short), "1K");
+        assert_eq!(format(1500., short), "1.5K");
+        assert_eq!(format(999_999., short), "999.99K");
+        assert_eq!(format(1e6, short), "1M");
+        assert_eq!(format(1_234_567., short), "1.23M");
+        assert_eq!(format(1e30, short), "1No");
+        // Past the last suffix
+        assert_eq!(format(1e33, short), "1e33");
+        assert_eq!(SparkAmount::from_log10(1_000.5).format(short), "3.16e1000");
+    }
+
+    #[test]
+    fn scientific_notation() {
+        let scientific = NumberNotation::Scientific;
+
+        assert_eq!(format(1000., scientific), "1e3");
+        assert_eq!(format(1234., scientific), "1.23e3");
+        assert_eq!(format(999_999., scientific), "9.99e5");
+        assert_eq!(format(1e6, scientific), "1e6");
+        assert_eq!(
+            SparkAmount::from_log10(1_000.5).format(scientific),
+            "3.16e1000"
+        );
+        assert_eq!(
+            SparkAmount::MAX.format(scientific),
+            format!("9.99e{}", i64::MAX)
+        );
+    }
+
+    #[test]
+    fn engineering_notation() {
+        let engineering = NumberNotation::Engineering;
+
+        assert_eq!(format(1000., engineering), "1e3");
+        assert_eq!(format(12_345., engineering), "12.34e3");
+        assert_eq!(format(999_999., engineering), "999.99e3");
+        assert_eq!(format(1e6, engineering), "1e6");
+        assert_eq!(
+            SparkAmount::from_log10(1_000.5).format(engineering),
+            "31.62e999"
+        );
+    }
+}
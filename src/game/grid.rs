@@ -1,7 +1,9 @@
 use bevy::{
     input::{keyboard::KeyboardInput, ButtonState},
     prelude::*,
+    window::PrimaryWindow,
 };
+use serde::{Deserialize, Serialize};
 use vello::{
     kurbo::Shape,
     peniko::{color::palette, Brush},
@@ -12,14 +14,25 @@ use crate::{
     canvas::world_canvas::{SyltPickingShape, SyltWorldScene},
     game::{
         build::ShowBuildMenu,
+        currency::SparkAmount,
+        node_menu::NodeMenu,
+        save::{GameStart, SavedGame},
+        scenario::Scenarios,
+        simulation::{SimulationAction, SparkSimulation, INITIAL_POSITION},
         system_set::{SyltGameSystemSet, SyltPausableSystems},
+        upgrades::UpgradePanel,
     },
+    menus::SyltMenuState,
     routes::SyltRouterState,
     vectors::{easings::ease_out_elastic, rectangle::SyltRectExt},
 };
 
-pub const GRID_WIDTH: i32 = 9;
-pub const GRID_HEIGHT: i32 = 9;
+pub const INITIAL_GRID_WIDTH: i32 = 9;
+pub const INITIAL_GRID_HEIGHT: i32 = 9;
+/// The board can not grow beyond this many cells in either direction
+pub const MAX_GRID_SIZE: i32 = 25;
+/// Sparks per added cell, multiplied by how much the board has grown
+const EXPANSION_COST_PER_CELL: f64 = 5.;
 pub const CELL_HEIGHT: f32 = 100.;
 pub const CELL_WIDTH: f32 = 100.;
 pub const CELL_GAP: f32 = 10.;
@@ -28,21 +41,39 @@ pub struct GridPlugin;
 
 impl Plugin for GridPlugin {
     fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(SyltRouterState::Game), insert_grid_position);
+
         app.add_systems(
-            OnEnter(SyltRouterState::Game),
-            (insert_grid_position, setup_grid),
+            OnExit(SyltRouterState::Game),
+            (remove_grid_position, remove_grid_bounds),
         );
 
-        app.add_systems(OnExit(SyltRouterState::Game), remove_grid_position);
-
         app.add_systems(
             Update,
-            (keyboard_input_system)
+            (keyboard_input_system, expand_grid_input)
                 .in_set(SyltPausableSystems)
                 .in_set(SyltGameSystemSet::Input)
                 .run_if(
                     in_state(SyltRouterState::Game)
-                        .and(resource_exists::<ShowBuildMenu>),
+                        .and(resource_exists::<ShowBuildMenu>)
+                        .and(resource_exists::<NodeMenu>)
+                        .and(resource_exists::<UpgradePanel>)
+                        .and(resource_exists::<GridBounds>)
+                        .and(resource_exists::<SparkSimulation>),
+                ),
+        );
+
+        app.add_systems(
+            Update,
+            (
+                sync_grid_bounds,
+                setup_grid.run_if(resource_changed::<GridBounds>),
+            )
+                .chain()
+                .in_set(SyltGameSystemSet::Update)
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<SparkSimulation>),
                 ),
         );
 
@@ -59,19 +90,28 @@ impl Plugin for GridPlugin {
                 .in_set(SyltPausableSystems)
                 .run_if(
                     in_state(SyltRouterState::Game)
-                        .and(resource_exists::<FocusedGridPosition>),
+                        .and(resource_exists::<FocusedGridPosition>)
+                        .and(resource_exists::<GridBounds>),
                 ),
         );
+
+        app.add_observer(on_cell_over)
+            .add_observer(on_cell_out)
+            .add_observer(on_cell_release);
     }
 }
 
 fn keyboard_input_system(
     display_shop: Res<ShowBuildMenu>,
+    node_menu: Res<NodeMenu>,
+    upgrade_panel: Res<UpgradePanel>,
     mut cmd: Commands,
     grid_position: Res<FocusedGridPosition>,
+    grid_bounds: Res<GridBounds>,
     mut keyboard_event_reader: EventReader<KeyboardInput>,
 ) {
-    if display_shop.0 {
+    // The focus can still move while carrying a node to its new cell
+    if display_shop.0 || *node_menu == NodeMenu::Open || upgrade_panel.open {
         return;
     }
 
@@ -110,12 +150,44 @@ fn keyboard_input_system(
         }
     }
 
+    let clamped = grid_bounds.clamp(IVec2::new(new_x, new_y));
+
     cmd.insert_resource(FocusedGridPosition {
-        x: new_x.clamp(0, 8),
-        y: new_y.clamp(0, 8),
+        x: clamped.x,
+        y: clamped.y,
     });
 }
 
+fn expand_grid_input(
+    display_shop: Res<ShowBuildMenu>,
+    node_menu: Res<NodeMenu>,
+    upgrade_panel: Res<UpgradePanel>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut simulation_actions: EventWriter<SimulationAction>,
+) {
+    if display_shop.0 || *node_menu != NodeMenu::Closed || upgrade_panel.open {
+        return;
+    }
+
+    // Ctrl+C copies a blueprint instead
+    if keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+    {
+        return;
+    }
+
+    let expansion = if keyboard_input.just_pressed(KeyCode::KeyR) {
+        GridExpansion::Row
+    } else if keyboard_input.just_pressed(KeyCode::KeyC) {
+        GridExpansion::Column
+    } else if keyboard_input.just_pressed(KeyCode::KeyX) {
+        GridExpansion::Ring
+    } else {
+        return;
+    };
+
+    simulation_actions.write(SimulationAction::ExpandGrid { expansion });
+}
+
 #[derive(Component)]
 struct MoveCameraAnimation {
     pub timer: Timer,
@@ -123,22 +195,94 @@ struct MoveCameraAnimation {
     pub to: Vec3,
 }
 
-fn insert_grid_position(mut cmd: Commands) {
-    cmd.insert_resource(FocusedGridPosition { x: 4, y: 4 });
+fn insert_grid_position(
+    mut cmd: Commands,
+    game_start: Res<GameStart>,
+    saved_game: Res<SavedGame>,
+    scenarios: Res<Scenarios>,
+) {
+    if let GameStart::Scenario(id) = &*game_start {
+        // Scenario boards start at the origin, focus their first node
+        let position = scenarios
+            .get(id)
+            .and_then(|scenario| scenario.nodes.first())
+            .map(|node| IVec2::new(node.x, node.y))
+            .unwrap_or_default();
+
+        cmd.insert_resource(FocusedGridPosition {
+            x: position.x,
+            y: position.y,
+        });
+        return;
+    }
+
+    match saved_game.to_restore(&game_start) {
+        Some(snapshot) => cmd.insert_resource(FocusedGridPosition {
+            x: snapshot.focused_x,
+            y: snapshot.focused_y,
+        }),
+        None => cmd.insert_resource(FocusedGridPosition {
+            x: INITIAL_POSITION.x,
+            y: INITIAL_POSITION.y,
+        }),
+    }
+}
+
+/// Camera coordinate along one axis that frames the focused cell. Boards
+/// that fit in the viewport stay centered, larger boards follow the focus
+/// without scrolling past their edges.
+fn frame_axis(
+    focused: i32,
+    first: i32,
+    last: i32,
+    cell_size: f32,
+    viewport: f32,
+    direction: f32,
+) -> f32 {
+    let step = cell_size + CELL_GAP;
+    let target = |index: i32| direction * index as f32 * step - step / 2.;
+
+    let (low, high) = {
+        let (a, b) = (target(first), target(last));
+        (a.min(b), a.max(b))
+    };
+
+    let board_size = high - low + step;
+
+    if board_size <= viewport {
+        return (low + high) / 2.;
+    }
+
+    let margin = (viewport - step) / 2.;
+    target(focused).clamp(low + margin, high - margin)
 }
 
 fn on_grid_position_changed(
     mut cmd: Commands,
     grid_position: Res<FocusedGridPosition>,
+    grid_bounds: Res<GridBounds>,
+    window: Single<&Window, With<PrimaryWindow>>,
     world_camera: Single<(Entity, &Transform), With<SyltWorldCamera>>,
 ) {
     let (camera_id, camera_transform) = *world_camera;
 
-    if grid_position.is_changed() {
-        let new_x = grid_position.x as f32 * (CELL_GAP + CELL_WIDTH)
-            - (CELL_GAP + CELL_WIDTH) / 2.;
-        let new_y = -(grid_position.y as f32) * (CELL_GAP + CELL_HEIGHT)
-            - (CELL_GAP + CELL_HEIGHT) / 2.;
+    if grid_position.is_changed() || grid_bounds.is_changed() {
+        let new_x = frame_axis(
+            grid_position.x,
+            grid_bounds.min_x,
+            grid_bounds.max_x,
+            CELL_WIDTH,
+            window.width(),
+            1.,
+        );
+        let new_y = frame_axis(
+            grid_position.y,
+            grid_bounds.min_y,
+            grid_bounds.max_y,
+            CELL_HEIGHT,
+            window.height(),
+            -1.,
+        );
 
         cmd.entity(camera_id).insert(MoveCameraAnimation {
             timer: Timer::from_seconds(0.2, TimerMode::Once),
@@ -175,6 +319,118 @@ fn remove_grid_position(mut cmd: Commands) {
     cmd.remove_resource::<FocusedGridPosition>();
 }
 
+fn remove_grid_bounds(mut cmd: Commands) {
+    cmd.remove_resource::<GridBounds>();
+}
+
+/// Mirrors the bounds owned by the simulation into the [`GridBounds`]
+/// resource
+fn sync_grid_bounds(
+    mut cmd: Commands,
+    simulation: Res<SparkSimulation>,
+    grid_bounds: Option<Res<GridBounds>>,
+) {
+    if grid_bounds.as_deref() != Some(&simulation.bounds) {
+        cmd.insert_resource(simulation.bounds);
+    }
+}
+
+/// The cells that can be built on, inclusive on both ends
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GridBounds {
+    pub min_x: i32,
+    pub min_y: i32,
+    pub max_x: i32,
+    pub max_y: i32,
+}
+
+impl Default for GridBounds {
+    fn default() -> Self {
+        Self {
+            min_x: 0,
+            min_y: 0,
+            max_x: INITIAL_GRID_WIDTH - 1,
+            max_y: INITIAL_GRID_HEIGHT - 1,
+        }
+    }
+}
+
+/// Ways the board can be grown
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridExpansion {
+    /// A new row below the board
+    Row,
+    /// A new column to the right of the board
+    Column,
+    /// A ring of cells around the whole board
+    Ring,
+}
+
+impl GridBounds {
+    pub fn width(&self) -> i32 {
+        self.max_x - self.min_x + 1
+    }
+
+    pub fn height(&self) -> i32 {
+        self.max_y - self.min_y + 1
+    }
+
+    pub fn area(&self) -> i32 {
+        self.width() * self.height()
+    }
+
+    pub fn contains(&self, position: IVec2) -> bool {
+        (self.min_x..=self.max_x).contains(&position.x)
+            && (self.min_y..=self.max_y).contains(&position.y)
+    }
+
+    pub fn clamp(&self, position: IVec2) -> IVec2 {
+        IVec2::new(
+            position.x.clamp(self.min_x, self.max_x),
+            position.y.clamp(self.min_y, self.max_y),
+        )
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = IVec2> + '_ {
+        (self.min_y..=self.max_y).flat_map(move |y| {
+            (self.min_x..=self.max_x).map(move |x| IVec2::new(x, y))
+        })
+    }
+
+    /// The grown bounds, or `None` if they would exceed [`MAX_GRID_SIZE`]
+    pub fn expanded(&self, expansion: GridExpansion) -> Option<Self> {
+        let expanded = match expansion {
+            GridExpansion::Row => Self {
+                max_y: self.max_y + 1,
+                ..*self
+            },
+            GridExpansion::Column => Self {
+                max_x: self.max_x + 1,
+                ..*self
+            },
+            GridExpansion::Ring => Self {
+                min_x: self.min_x - 1,
+                min_y: self.min_y - 1,
+                max_x: self.max_x + 1,
+                max_y: self.max_y + 1,
+            },
+        };
+
+        (expanded.width() <= MAX_GRID_SIZE
+            && expanded.height() <= MAX_GRID_SIZE)
+            .then_some(expanded)
+    }
+
+    /// Every added cell costs more the larger the board already is
+    pub fn expansion_cost(&self, expanded: &Self) -> SparkAmount {
+        let added = (expanded.area() - self.area()) as f64;
+        let growth = self.area() as f64
+            / (INITIAL_GRID_WIDTH * INITIAL_GRID_HEIGHT) as f64;
+
+        SparkAmount::from(added * EXPANSION_COST_PER_CELL * growth.powi(2))
+    }
+}
+
 #[derive(Resource)]
 pub struct FocusedGridPosition {
     pub x: i32,
@@ -208,56 +464,176 @@ struct GridCell {
     pub y: i32,
 }
 
-fn setup_grid(mut cmd: Commands) {
-    for i in 0..GRID_WIDTH {
-        for j in 0..GRID_HEIGHT {
-            cmd.spawn((
-                StateScoped(SyltRouterState::Game),
-                GridCell { x: i, y: j },
-                SyltWorldScene {
-                    pixel_size: Vec2::new(CELL_WIDTH, CELL_HEIGHT),
-                    ..default()
-                },
-                Transform::from_xyz(
-                    i as f32 * (CELL_WIDTH + CELL_GAP),
-                    -(j as f32) * (CELL_HEIGHT + CELL_GAP),
-                    0.,
-                ),
-            ));
+/// Marks the cell under the pointer
+#[derive(Component)]
+struct HoveredGridCell;
+
+fn on_cell_over(
+    trigger: Trigger<Pointer<Over>>,
+    mut cmd: Commands,
+    cell_q: Query<(), With<GridCell>>,
+) {
+    if cell_q.contains(trigger.target) {
+        cmd.entity(trigger.target).insert(HoveredGridCell);
+    }
+}
+
+fn on_cell_out(
+    trigger: Trigger<Pointer<Out>>,
+    mut cmd: Commands,
+    cell_q: Query<(), With<GridCell>>,
+) {
+    if cell_q.contains(trigger.target) {
+        cmd.entity(trigger.target).remove::<HoveredGridCell>();
+    }
+}
+
+/// Focuses the released cell, a second click on an empty focused cell opens
+/// the build menu
+#[allow(clippy::too_many_arguments)]
+fn on_cell_release(
+    trigger: Trigger<Pointer<Released>>,
+    mut cmd: Commands,
+    cell_q: Query<&GridCell>,
+    grid_position: Option<Res<FocusedGridPosition>>,
+    display_shop: Option<ResMut<ShowBuildMenu>>,
+    node_menu: Option<Res<NodeMenu>>,
+    upgrade_panel: Option<Res<UpgradePanel>>,
+    simulation: Option<Res<SparkSimulation>>,
+    mut menu_state: ResMut<NextState<SyltMenuState>>,
+) {
+    if trigger.button != PointerButton::Primary {
+        return;
+    }
+
+    let Ok(cell) = cell_q.get(trigger.target) else {
+        return;
+    };
+
+    let (
+        Some(grid_position),
+        Some(mut display_shop),
+        Some(node_menu),
+        Some(upgrade_panel),
+        Some(simulation),
+    ) = (
+        grid_position,
+        display_shop,
+        node_menu,
+        upgrade_panel,
+        simulation,
+    )
+    else {
+        return;
+    };
+
+    if display_shop.0 || *node_menu == NodeMenu::Open || upgrade_panel.open {
+        return;
+    }
+
+    let position = IVec2::new(cell.x, cell.y);
+
+    if grid_position.x != cell.x || grid_position.y != cell.y {
+        cmd.insert_resource(FocusedGridPosition::new(cell.x, cell.y));
+        return;
+    }
+
+    if *node_menu == NodeMenu::Closed
+        && !simulation.nodes.contains_key(&position)
+        && !simulation.is_blocked(position)
+    {
+        menu_state.set(SyltMenuState::Disabled);
+        display_shop.0 = true;
+    }
+}
+
+/// Spawns the cells within the bounds that do not exist yet
+fn setup_grid(
+    mut cmd: Commands,
+    grid_bounds: Res<GridBounds>,
+    cell_q: Query<&GridCell>,
+) {
+    for cell in grid_bounds.cells() {
+        if cell_q.iter().any(|c| c.x == cell.x && c.y == cell.y) {
+            continue;
         }
+
+        cmd.spawn((
+            StateScoped(SyltRouterState::Game),
+            GridCell {
+                x: cell.x,
+                y: cell.y,
+            },
+            SyltWorldScene {
+                pixel_size: Vec2::new(CELL_WIDTH, CELL_HEIGHT),
+                ..default()
+            },
+            SyltPickingShape {
+                inner: cell_rect().to_path(0.1),
+                ..default()
+            },
+            Transform::from_xyz(
+                cell.x as f32 * (CELL_WIDTH + CELL_GAP),
+                -(cell.y as f32) * (CELL_HEIGHT + CELL_GAP),
+                0.,
+            ),
+        ));
     }
 }
 
+fn cell_rect() -> vello::kurbo::Rect {
+    vello::kurbo::Rect::new(0., 0., CELL_WIDTH as f64, CELL_HEIGHT as f64)
+}
+
 fn draw_grid(
-    mut cmd: Commands,
-    cell_q: Query<(Entity, &mut SyltWorldScene, &GridCell)>,
+    cell_q: Query<(&mut SyltWorldScene, &GridCell, Has<HoveredGridCell>)>,
     grid_position: Res<FocusedGridPosition>,
+    simulation: Option<Res<SparkSimulation>>,
 ) {
-    for (entity, mut scene, cell) in cell_q {
+    for (mut scene, cell, hovered) in cell_q {
         let scene = &mut scene.inner;
 
         scene.reset();
 
-        let shape = vello::kurbo::Rect::new(
-            0.,
-            0.,
-            CELL_WIDTH as f64,
-            CELL_HEIGHT as f64,
-        );
+        let shape = cell_rect();
+
+        let blocked = simulation.as_ref().is_some_and(|simulation| {
+            simulation.is_blocked(IVec2::new(cell.x, cell.y))
+        });
 
-        // cmd.entity(entity).insert(SyltPickingShape {
-        //     inner: shape.to_path(0.1),
-        //     ..default()
-        // });
+        let color = if blocked {
+            palette::css::DARK_SLATE_GRAY
+        } else if hovered {
+            palette::css::GRAY
+        } else {
+            palette::css::DIM_GRAY
+        };
 
         scene.fill(
             vello::peniko::Fill::NonZero,
             Default::default(),
-            &Brush::Solid(palette::css::DIM_GRAY),
+            &Brush::Solid(color),
             None,
             &shape,
         );
 
+        // Scenarios block cells, they are crossed out
+        if blocked {
+            let mut cross = vello::kurbo::BezPath::new();
+            cross.move_to((20., 20.));
+            cross.line_to((CELL_WIDTH as f64 - 20., CELL_HEIGHT as f64 - 20.));
+            cross.move_to((CELL_WIDTH as f64 - 20., 20.));
+            cross.line_to((20., CELL_HEIGHT as f64 - 20.));
+
+            scene.stroke(
+                &vello::kurbo::Stroke::new(4.),
+                Default::default(),
+                &Brush::Solid(palette::css::DIM_GRAY),
+                None,
+                &cross,
+            );
+        }
+
         if cell.x == grid_position.x && cell.y == grid_position.y {
             let focus_shape = shape.plot_rect_corners(10., 5.);
 
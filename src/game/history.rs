@@ -0,0 +1,300 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{
+    game::{
+        build::ShowBuildMenu,
+        node_menu::NodeMenu,
+        simulation::{
+            ReversibleAction, SimulationAction, SimulationEvent,
+            SparkSimulation,
+        },
+        system_set::{SyltGameSystemSet, SyltPausableSystems},
+        upgrades::UpgradePanel,
+    },
+    routes::SyltRouterState,
+    settings::SyltSettings,
+};
+
+/// Number of actions that can be undone in a row
+pub const MAX_HISTORY: usize = 32;
+
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(SyltRouterState::Game), insert_history);
+
+        app.add_systems(OnExit(SyltRouterState::Game), remove_history);
+
+        app.add_systems(
+            Update,
+            on_keyboard_press
+                .in_set(SyltPausableSystems)
+                .in_set(SyltGameSystemSet::Input)
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<ShowBuildMenu>)
+                        .and(resource_exists::<NodeMenu>)
+                        .and(resource_exists::<UpgradePanel>)
+                        .and(resource_exists::<SparkSimulation>)
+                        .and(resource_exists::<ActionHistory>),
+                ),
+        );
+
+        app.add_systems(
+            Update,
+            record_actions
+                .in_set(SyltPausableSystems)
+                .in_set(SyltGameSystemSet::Update)
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<ActionHistory>),
+                ),
+        );
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HistoryEntry {
+    action: ReversibleAction,
+    /// Elapsed game seconds when the action was first taken, redoing keeps
+    /// it so the undo window can not be extended
+    taken_at: f64,
+}
+
+/// Recent board actions, the newest last. Undone actions can be redone until
+/// a new action is taken.
+#[derive(Resource, Debug, Default)]
+pub struct ActionHistory {
+    undo: VecDeque<HistoryEntry>,
+    redo: Vec<HistoryEntry>,
+}
+
+impl ActionHistory {
+    fn record(&mut self, action: ReversibleAction, taken_at: f64) {
+        self.undo.push_back(HistoryEntry { action, taken_at });
+        self.redo.clear();
+
+        while self.undo.len() > MAX_HISTORY {
+            self.undo.pop_front();
+        }
+    }
+
+    /// Forgets the actions older than the window, they stay permanent. A
+    /// window of zero turns undoing off, even for actions taken this instant.
+    fn expire(&mut self, now: f64, window: f64) {
+        if window <= 0. {
+            self.undo.clear();
+            return;
+        }
+
+        self.undo.retain(|entry| now - entry.taken_at <= window);
+    }
+
+    /// Moves the newest action over to the redo list once it was undone
+    fn undone(&mut self, action: &ReversibleAction) {
+        if self.undo.back().is_none_or(|entry| entry.action != *action) {
+            return;
+        }
+
+        if let Some(entry) = self.undo.pop_back() {
+            self.redo.push(entry);
+        }
+    }
+
+    /// Moves the last undone action back once it was redone
+    fn redone(&mut self, action: &ReversibleAction) {
+        if self.redo.last().is_none_or(|entry| entry.action != *action) {
+            return;
+        }
+
+        if let Some(entry) = self.redo.pop() {
+            self.undo.push_back(entry);
+        }
+    }
+}
+
+fn insert_history(mut cmd: Commands) {
+    cmd.insert_resource(ActionHistory::default());
+}
+
+fn remove_history(mut cmd: Commands) {
+    cmd.remove_resource::<ActionHistory>();
+}
+
+fn record_actions(
+    time: Res<Time>,
+    mut history: ResMut<ActionHistory>,
+    mut simulation_events: EventReader<SimulationEvent>,
+) {
+    for event in simulation_events.read() {
+        match event {
+            SimulationEvent::Undone(action) => history.undone(action),
+            SimulationEvent::Redone(action) => history.redone(action),
+            event => {
+                if let Some(action) = ReversibleAction::from_event(event) {
+                    history.record(action, time.elapsed_secs_f64());
+                }
+            }
+        }
+    }
+}
+
+/// Ctrl+Z or U undoes the last action, Ctrl+Y redoes it. The history only
+/// changes once the simulation reports the action undone or redone.
+#[allow(clippy::too_many_arguments)]
+fn on_keyboard_press(
+    time: Res<Time>,
+    settings: Res<SyltSettings>,
+    display_shop: Res<ShowBuildMenu>,
+    node_menu: Res<NodeMenu>,
+    upgrade_panel: Res<UpgradePanel>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut history: ResMut<ActionHistory>,
+    mut simulation_actions: EventWriter<SimulationAction>,
+) {
+    if display_shop.0 || *node_menu != NodeMenu::Closed || upgrade_panel.open {
+        return;
+    }
+
+    let control = keyboard_input
+        .any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+
+    let undo = (control && keyboard_input.just_pressed(KeyCode::KeyZ))
+        || (!control && keyboard_input.just_pressed(KeyCode::KeyU));
+    let redo = control && keyboard_input.just_pressed(KeyCode::KeyY);
+
+    if !undo && !redo {
+        return;
+    }
+
+    history.expire(time.elapsed_secs_f64(), settings.undo_window.0 as f64);
+
+    let action = if undo {
+        history
+            .undo
+            .back()
+            .map(|entry| SimulationAction::Undo(entry.action.clone()))
+    } else {
+        history
+            .redo
+            .last()
+            .map(|entry| SimulationAction::Redo(entry.action.clone()))
+    };
+
+    match action {
+        Some(action) => {
+            simulation_actions.write(action);
+        }
+        None => debug!("Nothing to undo or redo"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::currency::SparkAmount;
+
+    fn build(x: i32) -> ReversibleAction {
+        ReversibleAction::Build {
+            kind: "relay".to_string(),
+            position: IVec2::new(x, 0),
+            cost: SparkAmount::from(10.),
+        }
+    }
+
+    fn undo_actions(history: &ActionHistory) -> Vec<ReversibleAction> {
+        history
+            .undo
+            .iter()
+            .map(|entry| entry.action.clone())
+            .collect()
+    }
+
+    #[test]
+    fn oldest_actions_are_evicted() {
+        let mut history = ActionHistory::default();
+
+        for x in 0..MAX_HISTORY as i32 + 2 {
+            history.record(build(x), 0.);
+        }
+
+        assert_eq!(history.undo.len(), MAX_HISTORY);
+        assert_eq!(
+            history.undo.front().map(|entry| &entry.action),
+            Some(&build(2))
+        );
+        assert_eq!(
+            history.undo.back().map(|entry| &entry.action),
+            Some(&build(MAX_HISTORY as i32 + 1))
+        );
+    }
+
+    #[test]
+    fn actions_expire_after_the_window() {
+        let mut history = ActionHistory::default();
+        history.record(build(0), 0.);
+        history.record(build(1), 20.);
+
+        history.expire(30., 30.);
+        assert_eq!(undo_actions(&history), vec![build(0), build(1)]);
+
+        history.expire(31., 30.);
+        assert_eq!(undo_actions(&history), vec![build(1)]);
+    }
+
+    #[test]
+    fn a_zero_window_turns_undoing_off() {
+        let mut history = ActionHistory::default();
+        history.record(build(0), 5.);
+
+        history.expire(5., 0.);
+        assert!(history.undo.is_empty());
+    }
+
+    #[test]
+    fn undone_actions_can_be_redone() {
+        let mut history = ActionHistory::default();
+        history.record(build(0), 0.);
+        history.record(build(1), 1.);
+
+        history.undone(&build(1));
+        assert_eq!(undo_actions(&history), vec![build(0)]);
+        assert_eq!(history.redo.len(), 1);
+
+        history.redone(&build(1));
+        assert_eq!(undo_actions(&history), vec![build(0), build(1)]);
+        assert!(history.redo.is_empty());
+
+        // Redoing keeps the time the action was first taken
+        assert_eq!(history.undo.back().map(|entry| entry.taken_at), Some(1.));
+    }
+
+    #[test]
+    fn new_actions_clear_the_redo_list() {
+        let mut history = ActionHistory::default();
+        history.record(build(0), 0.);
+        history.undone(&build(0));
+        assert_eq!(history.redo.len(), 1);
+
+        history.record(build(1), 1.);
+        assert!(history.redo.is_empty());
+
+        history.redone(&build(0));
+        assert_eq!(undo_actions(&history), vec![build(1)]);
+    }
+
+    #[test]
+    fn other_actions_are_not_moved() {
+        let mut history = ActionHistory::default();
+        history.record(build(0), 0.);
+        history.record(build(1), 1.);
+
+        // Only the newest action can be undone
+        history.undone(&build(0));
+        assert_eq!(undo_actions(&history), vec![build(0), build(1)]);
+        assert!(history.redo.is_empty());
+    }
+}
@@ -0,0 +1,454 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use bevy::{prelude::*, render::view::RenderLayers, ui::ContentSize};
+
+use crate::{
+    canvas::{
+        text::{SyltText, SyltTextAlign, SyltTextStyle},
+        ui_canvas::SyltUiText,
+    },
+    game::{
+        currency::SparkAmount,
+        simulation::{EarnedFrom, SparkSimulation, SparksEarned},
+        system_set::{SyltGameSystemSet, SyltPausableSystems},
+    },
+    i18n::SyltI18nText,
+    routes::SyltRouterState,
+    settings::SyltSettings,
+    ui::constants::SU4,
+};
+
+/// Seconds of income kept, enough for the longest window
+const HISTORY_SECONDS: usize = 60;
+/// Windows the rate is averaged over in the detail panel, in seconds
+const RATE_WINDOWS: [(usize, &str); 3] = [
+    (1, "income last second"),
+    (10, "income last 10 seconds"),
+    (60, "income last minute"),
+];
+/// Window of the rate shown next to the total and of the breakdown
+const SUMMARY_WINDOW: usize = 10;
+
+pub struct IncomePlugin;
+
+impl Plugin for IncomePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(SyltRouterState::Game), insert_income_meter);
+
+        app.add_systems(OnExit(SyltRouterState::Game), remove_income_meter);
+
+        app.add_systems(
+            Update,
+            toggle_income_panel
+                .in_set(SyltPausableSystems)
+                .in_set(SyltGameSystemSet::Input)
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<IncomePanel>),
+                ),
+        );
+
+        app.add_systems(
+            Update,
+            (record_income, update_income_text, update_income_panel)
+                .chain()
+                .in_set(SyltPausableSystems)
+                .in_set(SyltGameSystemSet::Update)
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<IncomeMeter>)
+                        .and(resource_exists::<IncomePanel>)
+                        .and(resource_exists::<SparkSimulation>),
+                ),
+        );
+    }
+}
+
+/// Where sparks came from, ordered with clicks first
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IncomeSource {
+    Click,
+    /// Pulses sent by a node of this type
+    Node(String),
+}
+
+#[derive(Debug, Clone, Default)]
+struct IncomeBucket {
+    total: SparkAmount,
+    by_source: BTreeMap<IncomeSource, SparkAmount>,
+}
+
+/// Sparks earned in each of the last seconds of game time, the newest last.
+/// Rates only count whole seconds so they do not jump around mid-second.
+#[derive(Resource, Debug, Default)]
+pub struct IncomeMeter {
+    /// The second of game time being filled
+    second: u64,
+    current: IncomeBucket,
+    completed: VecDeque<IncomeBucket>,
+}
+
+impl IncomeMeter {
+    fn starting_at(now: f64) -> Self {
+        Self {
+            second: now as u64,
+            ..default()
+        }
+    }
+
+    /// Closes the seconds that passed, seconds without income count as zero
+    fn advance(&mut self, now: f64) {
+        let second = now as u64;
+
+        if second.saturating_sub(self.second) > HISTORY_SECONDS as u64 {
+            self.completed.clear();
+            self.current = IncomeBucket::default();
+            self.second = second;
+        }
+
+        while self.second < second {
+            self.completed.push_back(std::mem::take(&mut self.current));
+            self.second += 1;
+
+            if self.completed.len() > HISTORY_SECONDS {
+                self.completed.pop_front();
+            }
+        }
+    }
+
+    fn record(&mut self, source: IncomeSource, amount: SparkAmount) {
+        self.current.total += amount;
+        *self.current.by_source.entry(source).or_default() += amount;
+    }
+
+    /// The last whole seconds, at most `window` of them
+    fn window(&self, window: usize) -> impl Iterator<Item = &IncomeBucket> {
+        self.completed.iter().rev().take(window)
+    }
+
+    /// Sparks per second over the last `window` seconds. Games younger than
+    /// the window are averaged over the seconds played so far.
+    pub fn rate(&self, window: usize) -> SparkAmount {
+        let mut total = SparkAmount::ZERO;
+        let mut seconds = 0;
+
+        for bucket in self.window(window) {
+            total += bucket.total;
+            seconds += 1;
+        }
+
+        per_second(total, seconds)
+    }
+
+    /// Sparks per second of every source over the last `window` seconds
+    pub fn breakdown(
+        &self,
+        window: usize,
+    ) -> BTreeMap<IncomeSource, SparkAmount> {
+        let mut totals: BTreeMap<IncomeSource, SparkAmount> = BTreeMap::new();
+        let mut seconds = 0;
+
+        for bucket in self.window(window) {
+            for (source, amount) in &bucket.by_source {
+                *totals.entry(source.clone()).or_default() += *amount;
+            }
+            seconds += 1;
+        }
+
+        totals
+            .into_iter()
+            .map(|(source, total)| (source, per_second(total, seconds)))
+            .collect()
+    }
+}
+
+fn per_second(total: SparkAmount, seconds: usize) -> SparkAmount {
+    if seconds == 0 {
+        return SparkAmount::ZERO;
+    }
+
+    total
+        .checked_mul(SparkAmount::from(1. / seconds as f64))
+        .unwrap_or(total)
+}
+
+/// Whether the income breakdown is shown
+#[derive(Resource, Debug, Default)]
+pub struct IncomePanel {
+    pub open: bool,
+    /// The meter second the panel was last filled in
+    shown_second: Option<u64>,
+}
+
+/// The rate next to the spark total, spawned with the score
+#[derive(Component)]
+pub struct IncomeRateText;
+
+#[derive(Component)]
+struct IncomePanelNode;
+
+fn insert_income_meter(mut cmd: Commands, time: Res<Time>) {
+    cmd.insert_resource(IncomeMeter::starting_at(time.elapsed_secs_f64()));
+    cmd.insert_resource(IncomePanel::default());
+
+    cmd.spawn((
+        StateScoped(SyltRouterState::Game),
+        IncomePanelNode,
+        Node {
+            position_type: PositionType::Absolute,
+            display: Display::None,
+            flex_direction: FlexDirection::Column,
+            top: Val::Px(SU4 * 12.),
+            left: Val::Px(10.),
+            row_gap: Val::Px(SU4),
+            ..default()
+        },
+        Pickable::IGNORE,
+    ));
+}
+
+fn remove_income_meter(mut cmd: Commands) {
+    cmd.remove_resource::<IncomeMeter>();
+    cmd.remove_resource::<IncomePanel>();
+}
+
+/// G shows or hides the income breakdown
+fn toggle_income_panel(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut panel: ResMut<IncomePanel>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyG) {
+        panel.open = !panel.open;
+        panel.shown_second = None;
+    }
+}
+
+fn record_income(
+    time: Res<Time>,
+    mut meter: ResMut<IncomeMeter>,
+    mut sparks_earned: EventReader<SparksEarned>,
+) {
+    meter.advance(time.elapsed_secs_f64());
+
+    for earned in sparks_earned.read() {
+        // Selling nodes and undoing purchases is not income
+        let source = match &earned.from {
+            EarnedFrom::Click { .. } => IncomeSource::Click,
+            EarnedFrom::Trigger { source_kind, .. } => {
+                IncomeSource::Node(source_kind.clone())
+            }
+            EarnedFrom::Sale | EarnedFrom::Refund => continue,
+        };
+
+        meter.record(source, earned.amount);
+    }
+}
+
+fn update_income_text(
+    meter: Res<IncomeMeter>,
+    settings: Res<SyltSettings>,
+    mut rate_text: Single<&mut SyltText, With<IncomeRateText>>,
+) {
+    let content = format!(
+        "+{}/s",
+        meter
+            .rate(SUMMARY_WINDOW)
+            .format_rate(settings.number_notation.0)
+    );
+
+    if rate_text.content != content {
+        rate_text.content = content;
+    }
+}
+
+fn panel_text() -> impl Bundle {
+    (
+        RenderLayers::layer(1),
+        SyltUiText,
+        SyltTextStyle {
+            font_size: 20.,
+            ..default()
+        },
+        SyltTextAlign::Left,
+        SyltText::default(),
+        Node::default(),
+        ContentSize::default(),
+    )
+}
+
+/// Fills in the panel when it opens and once every second while open
+fn update_income_panel(
+    mut cmd: Commands,
+    meter: Res<IncomeMeter>,
+    simulation: Res<SparkSimulation>,
+    settings: Res<SyltSettings>,
+    mut panel: ResMut<IncomePanel>,
+    panel_node: Single<(Entity, &mut Node), With<IncomePanelNode>>,
+) {
+    let (panel_entity, mut node) = panel_node.into_inner();
+
+    let display = if panel.open {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    if node.display != display {
+        node.display = display;
+    }
+
+    if !panel.open || panel.shown_second == Some(meter.second) {
+        return;
+    }
+    panel.shown_second = Some(meter.second);
+
+    let notation = settings.number_notation.0;
+    let mut rows: Vec<(String, String)> = RATE_WINDOWS
+        .iter()
+        .map(|(window, key)| {
+            let rate = meter.rate(*window).format_rate(notation);
+            (key.to_string(), format!("+{rate}/s"))
+        })
+        .collect();
+
+    for (source, rate) in meter.breakdown(SUMMARY_WINDOW) {
+        let key = match &source {
+            IncomeSource::Click => "income clicks".to_string(),
+            IncomeSource::Node(kind) => simulation
+                .definition(kind)
+                .map(|definition| definition.i18n_key.clone())
+                .unwrap_or_else(|| kind.clone()),
+        };
+
+        rows.push((key, format!("+{}/s", rate.format_rate(notation))));
+    }
+
+    cmd.entity(panel_entity)
+        .despawn_related::<Children>()
+        .with_children(|parent| {
+            for (key, value) in rows {
+                parent
+                    .spawn(Node {
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(SU4 * 2.),
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn((panel_text(), SyltI18nText::from_key(&key)));
+                        row.spawn((
+                            panel_text(),
+                            SyltText {
+                                content: value,
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sparks(amount: f64) -> SparkAmount {
+        SparkAmount::from(amount)
+    }
+
+    fn node(kind: &str) -> IncomeSource {
+        IncomeSource::Node(kind.to_string())
+    }
+
+    #[test]
+    fn only_whole_seconds_count() {
+        let mut meter = IncomeMeter::starting_at(0.);
+        meter.record(IncomeSource::Click, sparks(3.));
+
+        meter.advance(0.9);
+        assert_eq!(meter.rate(1), SparkAmount::ZERO);
+
+        meter.advance(1.2);
+        assert_eq!(meter.rate(1), sparks(3.));
+        meter.record(IncomeSource::Click, sparks(5.));
+
+        // Seconds without income count as zero
+        meter.advance(3.);
+        assert_eq!(meter.rate(1), SparkAmount::ZERO);
+        assert_eq!(meter.rate(3), sparks(8. / 3.));
+    }
+
+    #[test]
+    fn young_games_average_over_the_seconds_played() {
+        let mut meter = IncomeMeter::starting_at(0.);
+
+        for _ in 0..4 {
+            meter.record(IncomeSource::Click, sparks(2.));
+            let second = meter.second;
+            meter.advance(second as f64 + 1.);
+        }
+
+        assert_eq!(meter.rate(60), sparks(2.));
+        assert_eq!(meter.rate(10), sparks(2.));
+    }
+
+    #[test]
+    fn old_seconds_roll_out_of_the_history() {
+        let mut meter = IncomeMeter::starting_at(0.);
+        meter.record(IncomeSource::Click, sparks(60.));
+
+        meter.advance(HISTORY_SECONDS as f64);
+        assert_eq!(meter.completed.len(), HISTORY_SECONDS);
+        assert_eq!(meter.rate(HISTORY_SECONDS), sparks(1.));
+
+        meter.advance(HISTORY_SECONDS as f64 + 1.);
+        assert_eq!(meter.completed.len(), HISTORY_SECONDS);
+        assert_eq!(meter.rate(HISTORY_SECONDS), SparkAmount::ZERO);
+    }
+
+    #[test]
+    fn long_gaps_start_the_history_over() {
+        let mut meter = IncomeMeter::starting_at(0.);
+        meter.record(IncomeSource::Click, sparks(10.));
+        meter.advance(1.);
+
+        meter.advance(1000.5);
+        assert!(meter.completed.is_empty());
+        assert_eq!(meter.second, 1000);
+        assert_eq!(meter.rate(60), SparkAmount::ZERO);
+
+        meter.record(IncomeSource::Click, sparks(4.));
+        meter.advance(1001.);
+        assert_eq!(meter.rate(60), sparks(4.));
+    }
+
+    #[test]
+    fn breakdown_by_source() {
+        let mut meter = IncomeMeter::starting_at(0.);
+        meter.record(IncomeSource::Click, sparks(1.));
+        meter.record(node("stimulator"), sparks(4.));
+        meter.advance(1.);
+        meter.record(node("stimulator"), sparks(2.));
+        meter.record(node("relay"), sparks(6.));
+        meter.advance(2.);
+
+        let breakdown = meter.breakdown(10);
+        assert_eq!(
+            breakdown.keys().collect::<Vec<_>>(),
+            vec![&IncomeSource::Click, &node("relay"), &node("stimulator")]
+        );
+        assert_eq!(breakdown[&IncomeSource::Click], sparks(0.5));
+        assert_eq!(breakdown[&node("relay")], sparks(3.));
+        assert_eq!(breakdown[&node("stimulator")], sparks(3.));
+
+        let last_second = meter.breakdown(1);
+        assert!(!last_second.contains_key(&IncomeSource::Click));
+        assert_eq!(last_second[&node("relay")], sparks(6.));
+    }
+
+    #[test]
+    fn no_seconds_no_rate() {
+        assert_eq!(per_second(sparks(10.), 0), SparkAmount::ZERO);
+        assert_eq!(per_second(sparks(10.), 4), sparks(2.5));
+        assert_eq!(IncomeMeter::default().rate(10), SparkAmount::ZERO);
+    }
+}
@@ -37,6 +37,18 @@ impl Plugin for InstructionsPlugin {
             spawn_gameplay_instructions
                 .run_if(resource_exists::<InstructionsWrapper>),
         );
+
+        app.add_systems(
+            OnEnter(InstructionState::NodeMenu),
+            spawn_node_menu_instructions
+                .run_if(resource_exists::<InstructionsWrapper>),
+        );
+
+        app.add_systems(
+            OnEnter(InstructionState::CarryNode),
+            spawn_carry_node_instructions
+                .run_if(resource_exists::<InstructionsWrapper>),
+        );
     }
 }
 
@@ -92,6 +104,27 @@ pub enum InstructionState {
     None,
     Gameplay,
     Build,
+    /// Choosing what to do with a placed node
+    NodeMenu,
+    /// Choosing where a picked up node goes
+    CarryNode,
+}
+
+fn instruction_text(state: InstructionState, key: &str) -> impl Bundle {
+    (
+        StateScoped(state),
+        RenderLayers::layer(1),
+        SyltUiText,
+        SyltTextStyle {
+            font_size: 28.,
+            ..default()
+        },
+        SyltTextAlign::Middle,
+        SyltText::default(),
+        SyltI18nText::from_key(key),
+        Node::default(),
+        ContentSize::default(),
+    )
 }
 
 fn spawn_build_instructions(
@@ -99,19 +132,9 @@ fn spawn_build_instructions(
     wrapper: Res<InstructionsWrapper>,
 ) {
     let child = cmd
-        .spawn((
-            StateScoped(InstructionState::Build),
-            RenderLayers::layer(1),
-            SyltUiText,
-            SyltTextStyle {
-                font_size: 28.,
-                ..default()
-            },
-            SyltTextAlign::Middle,
-            SyltText::default(),
-            SyltI18nText::from_key("build instructions"),
-            Node::default(),
-            ContentSize::default(),
+        .spawn(instruction_text(
+            InstructionState::Build,
+            "build instructions",
         ))
         .id();
 
@@ -123,19 +146,37 @@ fn spawn_gameplay_instructions(
     wrapper: Res<InstructionsWrapper>,
 ) {
     let child = cmd
-        .spawn((
-            StateScoped(InstructionState::Gameplay),
-            RenderLayers::layer(1),
-            SyltUiText,
-            SyltTextStyle {
-                font_size: 28.,
-                ..default()
-            },
-            SyltTextAlign::Middle,
-            SyltText::default(),
-            SyltI18nText::from_key("gameplay instructions"),
-            Node::default(),
-            ContentSize::default(),
+        .spawn(instruction_text(
+            InstructionState::Gameplay,
+            "gameplay instructions",
+        ))
+        .id();
+
+    cmd.entity(**wrapper).add_child(child);
+}
+
+fn spawn_node_menu_instructions(
+    mut cmd: Commands,
+    wrapper: Res<InstructionsWrapper>,
+) {
+    let child = cmd
+        .spawn(instruction_text(
+            InstructionState::NodeMenu,
+            "node menu instructions",
+        ))
+        .id();
+
+    cmd.entity(**wrapper).add_child(child);
+}
+
+fn spawn_carry_node_instructions(
+    mut cmd: Commands,
+    wrapper: Res<InstructionsWrapper>,
+) {
+    let child = cmd
+        .spawn(instruction_text(
+            InstructionState::CarryNode,
+            "carry node instructions",
         ))
         .id();
 
@@ -7,15 +7,35 @@ use crate::{
     settings::SyltSettings,
     signals::SyltSignal,
     sounds::{loop_music, SyltSoundAssets},
+    ui::constants::SU4,
 };
 
 pub mod system_set;
+use simulation::SparkSimulation;
 use system_set::SyltPausableSystems;
 
+pub mod achievements;
+mod beams;
+mod blueprint;
 mod build;
+pub mod catalog;
+pub mod currency;
 mod grid;
+mod history;
+mod income;
 mod instructions;
+mod node_menu;
 mod nodes;
+mod offline;
+mod popups;
+pub mod prestige;
+mod pulses;
+pub mod save;
+pub mod scenario;
+pub mod simulation;
+mod speed;
+pub mod statistics;
+mod upgrades;
 
 pub struct SyltGamePlugin;
 
@@ -25,24 +45,39 @@ impl Plugin for SyltGamePlugin {
 
         // Add game specific plugins here
         app.add_plugins((
+            catalog::CatalogPlugin,
             grid::GridPlugin,
             nodes::NodesPlugin,
             build::BuildPlugin,
+            node_menu::NodeMenuPlugin,
             instructions::InstructionsPlugin,
+            simulation::SimulationPlugin,
+            save::SavePlugin,
+            offline::OfflinePlugin,
+            upgrades::UpgradesPlugin,
+            prestige::PrestigePlugin,
+            achievements::AchievementsPlugin,
+            statistics::StatisticsPlugin,
+            popups::PopupsPlugin,
+            beams::BeamsPlugin,
         ));
 
-        app.insert_resource(Sparks(0.0));
+        app.add_plugins((
+            history::HistoryPlugin,
+            blueprint::BlueprintPlugin,
+            scenario::ScenarioPlugin,
+            speed::SpeedPlugin,
+            income::IncomePlugin,
+        ));
 
-        app.add_systems(
-            OnEnter(SyltRouterState::Game),
-            (init_game_state, spawn_score),
-        );
+        app.add_systems(OnEnter(SyltRouterState::Game), spawn_score);
 
         app.add_systems(
             Update,
-            (update_sparks_text)
-                .in_set(SyltPausableSystems)
-                .run_if(in_state(SyltRouterState::Game)),
+            (update_sparks_text).in_set(SyltPausableSystems).run_if(
+                in_state(SyltRouterState::Game)
+                    .and(resource_exists::<SparkSimulation>),
+            ),
         );
 
         app.add_systems(OnEnter(SyltRouterState::Splash), start_game_music);
@@ -57,8 +92,9 @@ fn start_game_music(
     cmd.spawn(loop_music(sounds.music_loop.clone(), &settings));
 }
 
-#[derive(Resource)]
-struct Sparks(pub f32);
+/// The spark balance, owned by the [`SparkSimulation`]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Sparks(pub currency::SparkAmount);
 
 #[derive(Component)]
 struct SparkNodeButton;
@@ -125,11 +161,20 @@ fn spawn_score(mut cmd: Commands) {
             SyltText::default(),
             SparksText,
         ));
-    });
-}
 
-fn init_game_state(mut cmd: Commands) {
-    cmd.insert_resource(Sparks(0.0));
+        parent.spawn((
+            RenderLayers::layer(1),
+            StateScoped(SyltRouterState::Game),
+            Node {
+                margin: UiRect::left(Val::Px(SU4 * 2.)),
+                ..default()
+            },
+            ContentSize::default(),
+            SyltUiText,
+            SyltText::default(),
+            income::IncomeRateText,
+        ));
+    });
 }
 
 #[derive(Component)]
@@ -137,7 +182,8 @@ struct SparksText;
 
 fn update_sparks_text(
     mut txt: Single<&mut SyltText, With<SparksText>>,
-    sparks: Res<Sparks>,
+    simulation: Res<SparkSimulation>,
+    settings: Res<SyltSettings>,
 ) {
-    txt.content = sparks.0.to_string();
+    txt.content = simulation.sparks.0.format(settings.number_notation.0);
 }
@@ -0,0 +1,574 @@
+use bevy::{prelude::*, render::view::RenderLayers};
+use vello::kurbo::Shape;
+
+use crate::{
+    canvas::{
+        text::{
+            SyltFontAxes, SyltText, SyltTextAlign, SyltTextAnchor,
+            SyltTextStyle,
+        },
+        ui_canvas::{
+            NoSyltUiScaling, SyltUiScene, SyltUiText, SyltUiUseWorldCoorindates,
+        },
+        world_canvas::SyltPickingShape,
+    },
+    game::{
+        build::ShowBuildMenu,
+        catalog::NodeCatalog,
+        grid::{FocusedGridPosition, CELL_GAP, CELL_HEIGHT, CELL_WIDTH},
+        instructions::InstructionState,
+        simulation::{SimulationAction, SimulationEvent, SparkSimulation},
+        system_set::{SyltGameSystemSet, SyltPausableSystems},
+        upgrades::UpgradePanel,
+    },
+    i18n::SyltI18nText,
+    menus::SyltMenuState,
+    routes::SyltRouterState,
+    settings::SyltSettings,
+};
+
+pub struct NodeMenuPlugin;
+
+impl Plugin for NodeMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(SyltRouterState::Game), add_node_menu);
+
+        app.add_systems(OnExit(SyltRouterState::Game), remove_node_menu);
+
+        app.add_systems(
+            Update,
+            (on_keyboard_press)
+                .in_set(SyltPausableSystems)
+                .in_set(SyltGameSystemSet::Input)
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<NodeMenu>)
+                        .and(resource_exists::<UpgradePanel>)
+                        .and(resource_exists::<ShowBuildMenu>)
+                        .and(resource_exists::<SparkSimulation>),
+                ),
+        );
+
+        app.add_systems(
+            Update,
+            close_node_menu_on_done
+                .in_set(SyltPausableSystems)
+                .in_set(SyltGameSystemSet::Update)
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<NodeMenu>),
+                ),
+        );
+
+        app.add_systems(
+            Update,
+            (
+                sync_node_menu.run_if(resource_changed::<NodeMenu>),
+                position_node_menu,
+                draw_node_menu,
+                draw_carried_node,
+            )
+                .chain()
+                .in_set(SyltPausableSystems)
+                .in_set(SyltGameSystemSet::Update)
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<NodeMenu>)
+                        .and(resource_exists::<SparkSimulation>),
+                ),
+        );
+    }
+}
+
+/// What can be done with a placed node
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NodeAction {
+    Sell,
+    Move,
+    Swap,
+}
+
+impl NodeAction {
+    const ALL: [NodeAction; 3] =
+        [NodeAction::Sell, NodeAction::Move, NodeAction::Swap];
+
+    fn i18n_key(&self) -> &'static str {
+        match self {
+            NodeAction::Sell => "sell node",
+            NodeAction::Move => "move node",
+            NodeAction::Swap => "swap node",
+        }
+    }
+}
+
+/// The action menu of an occupied cell. Changes go through [`Commands`] so
+/// other input systems still see the previous state during the same frame.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+pub enum NodeMenu {
+    #[default]
+    Closed,
+    Open,
+    /// A node was picked up to be moved or swapped with the focused cell
+    Carrying {
+        from: IVec2,
+        action: NodeAction,
+    },
+}
+
+/// Index of the highlighted entry in [`NodeAction::ALL`]
+#[derive(Resource)]
+struct NodeMenuSelection(pub usize);
+
+fn add_node_menu(mut cmd: Commands) {
+    cmd.insert_resource(NodeMenu::Closed);
+    cmd.insert_resource(NodeMenuSelection(0));
+}
+
+fn remove_node_menu(mut cmd: Commands) {
+    cmd.remove_resource::<NodeMenu>();
+    cmd.remove_resource::<NodeMenuSelection>();
+}
+
+const HOTKEYS: [KeyCode; 3] =
+    [KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3];
+
+const CONFIRM_KEYS: [KeyCode; 4] =
+    [KeyCode::Space, KeyCode::Enter, KeyCode::KeyB, KeyCode::KeyI];
+
+fn close_node_menu(
+    cmd: &mut Commands,
+    menu_state: &mut NextState<SyltMenuState>,
+) {
+    cmd.insert_resource(NodeMenu::Closed);
+    menu_state.set(SyltMenuState::None);
+}
+
+/// The menu closes once the node is sold, moved or swapped, rejected
+/// actions keep it open
+fn close_node_menu_on_done(
+    mut cmd: Commands,
+    node_menu: Res<NodeMenu>,
+    mut menu_state: ResMut<NextState<SyltMenuState>>,
+    mut simulation_events: EventReader<SimulationEvent>,
+) {
+    let done = simulation_events.read().any(|event| {
+        matches!(
+            event,
+            SimulationEvent::Sold { .. }
+                | SimulationEvent::Moved { .. }
+                | SimulationEvent::Swapped { .. }
+        )
+    });
+
+    if done && *node_menu != NodeMenu::Closed {
+        close_node_menu(&mut cmd, &mut menu_state);
+    }
+}
+
+/// Sells right away, moving and swapping pick the node up first
+fn activate(
+    cmd: &mut Commands,
+    simulation_actions: &mut EventWriter<SimulationAction>,
+    action: NodeAction,
+    position: IVec2,
+) {
+    match action {
+        NodeAction::Sell => {
+            simulation_actions.write(SimulationAction::Sell { position });
+        }
+        NodeAction::Move | NodeAction::Swap => {
+            cmd.insert_resource(NodeMenu::Carrying {
+                from: position,
+                action,
+            });
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn on_keyboard_press(
+    mut cmd: Commands,
+    node_menu: Res<NodeMenu>,
+    mut selection: ResMut<NodeMenuSelection>,
+    display_shop: Res<ShowBuildMenu>,
+    upgrade_panel: Res<UpgradePanel>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focused_grid_position: Res<FocusedGridPosition>,
+    mut menu_state: ResMut<NextState<SyltMenuState>>,
+    simulation: Res<SparkSimulation>,
+    mut simulation_actions: EventWriter<SimulationAction>,
+) {
+    let position = IVec2::new(focused_grid_position.x, focused_grid_position.y);
+
+    match *node_menu {
+        NodeMenu::Closed => {
+            if display_shop.0
+                || upgrade_panel.open
+                || !keyboard_input.just_pressed(KeyCode::KeyB)
+                || !simulation.nodes.contains_key(&position)
+            {
+                return;
+            }
+
+            selection.0 = 0;
+            menu_state.set(SyltMenuState::Disabled);
+            cmd.insert_resource(NodeMenu::Open);
+        }
+        NodeMenu::Open => {
+            if keyboard_input.just_pressed(KeyCode::Escape) {
+                close_node_menu(&mut cmd, &mut menu_state);
+                return;
+            }
+
+            let item_count = NodeAction::ALL.len();
+
+            if keyboard_input.any_just_pressed([
+                KeyCode::KeyA,
+                KeyCode::KeyH,
+                KeyCode::ArrowLeft,
+            ]) {
+                selection.0 = (selection.0 + item_count - 1) % item_count;
+            }
+
+            if keyboard_input.any_just_pressed([
+                KeyCode::KeyD,
+                KeyCode::KeyL,
+                KeyCode::ArrowRight,
+            ]) {
+                selection.0 = (selection.0 + 1) % item_count;
+            }
+
+            let hotkey = HOTKEYS
+                .iter()
+                .position(|key| keyboard_input.just_pressed(*key));
+            let confirmed = keyboard_input.any_just_pressed(CONFIRM_KEYS);
+
+            let Some(index) = hotkey.or(confirmed.then_some(selection.0))
+            else {
+                return;
+            };
+
+            selection.0 = index;
+
+            activate(
+                &mut cmd,
+                &mut simulation_actions,
+                NodeAction::ALL[index],
+                position,
+            );
+        }
+        NodeMenu::Carrying { from, action } => {
+            if keyboard_input.just_pressed(KeyCode::Escape) {
+                close_node_menu(&mut cmd, &mut menu_state);
+                return;
+            }
+
+            if !keyboard_input.any_just_pressed(CONFIRM_KEYS) {
+                return;
+            }
+
+            let simulation_action = match action {
+                NodeAction::Swap => SimulationAction::Swap {
+                    first: from,
+                    second: position,
+                },
+                _ => SimulationAction::Move { from, to: position },
+            };
+
+            simulation_actions.write(simulation_action);
+        }
+    }
+}
+
+const MENU_OFFSET: f32 = 10.;
+
+/// Root of everything the node menu shows, follows the focused cell
+#[derive(Component)]
+struct NodeMenuRoot;
+
+#[derive(Component)]
+struct NodeMenuItems;
+
+#[derive(Component)]
+struct NodeMenuItem(pub usize);
+
+#[derive(Component)]
+struct CarriedNode;
+
+fn node_menu_text_style(font_size: f32) -> SyltTextStyle {
+    SyltTextStyle {
+        font_size,
+        font_axes: SyltFontAxes {
+            weight: Some(900.),
+            ..default()
+        },
+        brush: vello::peniko::Brush::Solid(vello::peniko::Color::WHITE),
+        ..default()
+    }
+}
+
+fn sync_node_menu(
+    mut cmd: Commands,
+    node_menu: Res<NodeMenu>,
+    root_q: Query<Entity, With<NodeMenuRoot>>,
+    focused_grid_position: Res<FocusedGridPosition>,
+    simulation: Res<SparkSimulation>,
+    settings: Res<SyltSettings>,
+    mut instruction_state: ResMut<NextState<InstructionState>>,
+) {
+    for root in &root_q {
+        cmd.entity(root).despawn();
+    }
+
+    match *node_menu {
+        NodeMenu::Closed => {
+            instruction_state.set(InstructionState::Gameplay);
+        }
+        NodeMenu::Open => {
+            instruction_state.set(InstructionState::NodeMenu);
+
+            let position =
+                IVec2::new(focused_grid_position.x, focused_grid_position.y);
+            let refund = simulation.refund(position).unwrap_or_default();
+
+            cmd.spawn((
+                RenderLayers::layer(1),
+                StateScoped(SyltRouterState::Game),
+                NodeMenuRoot,
+                NodeMenuItems,
+                NoSyltUiScaling,
+                SyltUiUseWorldCoorindates,
+                SyltUiScene::default(),
+            ))
+            .with_children(|parent| {
+                for (index, action) in NodeAction::ALL.iter().enumerate() {
+                    let offset_x = index as f32 * (CELL_WIDTH + MENU_OFFSET);
+
+                    parent
+                        .spawn((
+                            NodeMenuItem(index),
+                            Transform::from_xyz(offset_x, 0., 400.),
+                            SyltPickingShape {
+                                inner: vello::kurbo::Rect::new(
+                                    -5.,
+                                    -5.,
+                                    5. + CELL_WIDTH as f64,
+                                    5. + CELL_HEIGHT as f64,
+                                )
+                                .to_path(0.1),
+                                ..default()
+                            },
+                        ))
+                        .observe(on_node_menu_item_over)
+                        .observe(on_node_menu_item_release);
+
+                    parent.spawn((
+                        RenderLayers::layer(1),
+                        Transform::from_xyz(offset_x + 2., 20., 400.),
+                        SyltUiText,
+                        SyltText {
+                            content: format!("{}", index + 1),
+                            ..default()
+                        },
+                        SyltTextAnchor::Left,
+                        SyltTextAlign::Left,
+                        node_menu_text_style(16.),
+                        SyltUiUseWorldCoorindates,
+                    ));
+
+                    parent.spawn((
+                        RenderLayers::layer(1),
+                        Transform::from_xyz(
+                            offset_x + CELL_WIDTH / 2.,
+                            -CELL_HEIGHT / 2.,
+                            400.,
+                        ),
+                        SyltUiText,
+                        SyltText::default(),
+                        SyltTextAnchor::Center,
+                        SyltTextAlign::Middle,
+                        SyltI18nText::from_key(action.i18n_key()),
+                        node_menu_text_style(20.),
+                        SyltUiUseWorldCoorindates,
+                    ));
+
+                    if *action == NodeAction::Sell {
+                        parent.spawn((
+                            RenderLayers::layer(1),
+                            Transform::from_xyz(
+                                offset_x + CELL_WIDTH / 2.,
+                                -CELL_HEIGHT - 20.,
+                                400.,
+                            ),
+                            SyltUiText,
+                            SyltText {
+                                content: format!(
+                                    "+{} SP",
+                                    refund.format(settings.number_notation.0)
+                                ),
+                                ..default()
+                            },
+                            SyltTextAnchor::Center,
+                            SyltTextAlign::Middle,
+                            node_menu_text_style(20.),
+                            SyltUiUseWorldCoorindates,
+                        ));
+                    }
+                }
+            });
+        }
+        NodeMenu::Carrying { .. } => {
+            instruction_state.set(InstructionState::CarryNode);
+
+            cmd.spawn((
+                RenderLayers::layer(1),
+                StateScoped(SyltRouterState::Game),
+                NodeMenuRoot,
+                CarriedNode,
+                NoSyltUiScaling,
+                SyltUiUseWorldCoorindates,
+                SyltUiScene::default(),
+            ));
+        }
+    }
+}
+
+fn on_node_menu_item_over(
+    trigger: Trigger<Pointer<Over>>,
+    item_q: Query<&NodeMenuItem>,
+    selection: Option<ResMut<NodeMenuSelection>>,
+) {
+    let (Ok(item), Some(mut selection)) =
+        (item_q.get(trigger.target), selection)
+    else {
+        return;
+    };
+
+    if selection.0 != item.0 {
+        selection.0 = item.0;
+    }
+}
+
+fn on_node_menu_item_release(
+    trigger: Trigger<Pointer<Released>>,
+    mut cmd: Commands,
+    item_q: Query<&NodeMenuItem>,
+    focused_grid_position: Option<Res<FocusedGridPosition>>,
+    mut simulation_actions: EventWriter<SimulationAction>,
+) {
+    if trigger.button != PointerButton::Primary {
+        return;
+    }
+
+    let Ok(item) = item_q.get(trigger.target) else {
+        return;
+    };
+
+    let Some(focused_grid_position) = focused_grid_position else {
+        return;
+    };
+
+    let Some(action) = NodeAction::ALL.get(item.0) else {
+        return;
+    };
+
+    activate(
+        &mut cmd,
+        &mut simulation_actions,
+        *action,
+        IVec2::new(focused_grid_position.x, focused_grid_position.y),
+    );
+}
+
+fn position_node_menu(
+    root_q: Query<&mut Transform, With<NodeMenuRoot>>,
+    grid_position: Res<FocusedGridPosition>,
+) {
+    for mut transform in root_q {
+        let new_x = grid_position.x as f32 * (CELL_GAP + CELL_WIDTH);
+        let new_y = -(grid_position.y as f32) * (CELL_GAP + CELL_HEIGHT);
+        transform.translation.x = new_x;
+        transform.translation.y = new_y;
+    }
+}
+
+fn draw_node_menu(
+    items_q: Query<&mut SyltUiScene, With<NodeMenuItems>>,
+    selection: Res<NodeMenuSelection>,
+) {
+    for mut scene in items_q {
+        let scene = &mut scene.inner;
+
+        scene.reset();
+
+        for index in 0..NodeAction::ALL.len() {
+            let transform = vello::kurbo::Affine::translate((
+                index as f64 * (CELL_WIDTH + MENU_OFFSET) as f64 - 5.,
+                -5.,
+            ));
+
+            let background = vello::kurbo::Rect::new(
+                0.,
+                0.,
+                10. + CELL_WIDTH as f64,
+                10. + CELL_HEIGHT as f64,
+            );
+
+            scene.fill(
+                vello::peniko::Fill::NonZero,
+                transform,
+                vello::peniko::Color::new([0., 0., 0., 0.7]),
+                None,
+                &background,
+            );
+
+            if index == selection.0 {
+                scene.stroke(
+                    &vello::kurbo::Stroke::new(4.),
+                    transform,
+                    vello::peniko::Color::WHITE,
+                    None,
+                    &background,
+                );
+            }
+        }
+    }
+}
+
+/// Shows the picked up node under the focus while choosing where it goes
+fn draw_carried_node(
+    carried_q: Query<&mut SyltUiScene, With<CarriedNode>>,
+    node_menu: Res<NodeMenu>,
+    simulation: Res<SparkSimulation>,
+    catalog: Res<NodeCatalog>,
+) {
+    let NodeMenu::Carrying { from, .. } = *node_menu else {
+        return;
+    };
+
+    let Some(definition) = simulation
+        .nodes
+        .get(&from)
+        .and_then(|node| catalog.get(&node.kind))
+    else {
+        return;
+    };
+
+    for mut scene in carried_q {
+        let scene = &mut scene.inner;
+
+        scene.reset();
+
+        let shape = definition
+            .shape
+            .to_path((CELL_WIDTH as f64 * 0.5, CELL_HEIGHT as f64 * 0.5));
+
+        scene.stroke(
+            &vello::kurbo::Stroke::new(4.),
+            vello::kurbo::Affine::default(),
+            definition.color().with_alpha(0.6),
+            None,
+            &shape,
+        );
+    }
+}
@@ -1,5 +1,4 @@
-use bevy::prelude::*;
-use vello::kurbo::Shape;
+use bevy::{platform::collections::HashMap, prelude::*};
 
 use crate::{
     canvas::world_canvas::{
@@ -7,25 +6,29 @@ use crate::{
     },
     game::{
         build::ShowBuildMenu,
+        catalog::{GridDirection, NodeCatalog},
         grid::{
             FocusedGridPosition, GridPosition, CELL_GAP, CELL_HEIGHT,
             CELL_WIDTH,
         },
+        node_menu::NodeMenu,
+        simulation::{
+            NodeTriggered, SimulationAction, SimulationEvent, SparkSimulation,
+            SparksEarned,
+        },
         system_set::{SyltGameSystemSet, SyltPausableSystems},
-        Sparks,
+        upgrades::UpgradePanel,
     },
     routes::SyltRouterState,
     settings::SyltSettings,
     sounds::{play_game_sound_despawn, SyltSoundAssets},
-    vectors::{bumps::bump_logistic, polygon::plot_polygon_path},
+    vectors::bumps::bump_logistic,
 };
 
 pub struct NodesPlugin;
 
 impl Plugin for NodesPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(SyltRouterState::Game), place_initial_node);
-
         app.add_systems(
             Update,
             (on_keyboard_press)
@@ -33,23 +36,30 @@ impl Plugin for NodesPlugin {
                 .in_set(SyltGameSystemSet::Input)
                 .run_if(
                     in_state(SyltRouterState::Game)
-                        .and(resource_exists::<ShowBuildMenu>),
+                        .and(resource_exists::<ShowBuildMenu>)
+                        .and(resource_exists::<NodeMenu>)
+                        .and(resource_exists::<UpgradePanel>)
+                        .and(resource_exists::<SparkSimulation>),
                 ),
         );
 
         app.add_systems(
             Update,
             (
-                draw_stimulator_node,
-                draw_clicker_node,
+                move_nodes,
+                sync_nodes,
+                draw_node,
                 position_node,
-                pulsate_stimulator_node,
-                animate_stimulator_node,
-                animate_clicker_node,
+                on_simulation_event,
+                animate_node,
             )
+                .chain()
                 .in_set(SyltPausableSystems)
                 .in_set(SyltGameSystemSet::Update)
-                .run_if(in_state(SyltRouterState::Game)),
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<SparkSimulation>),
+                ),
         );
 
         app.add_observer(on_pointer_release);
@@ -57,34 +67,100 @@ impl Plugin for NodesPlugin {
 }
 
 #[derive(Component, Default)]
+#[require(SyltWorldScene)]
 pub struct SparkNode {
+    /// Id of the node definition in the [`NodeCatalog`]
+    pub kind: String,
     pub original_translation: Vec3,
-}
-
-pub const CLICKER_NODE_COST: f32 = 10.;
-#[derive(Component, Default)]
-#[require(SyltWorldScene)]
-pub struct ClickerNode {
     pub animation_timer: Timer,
 }
 
-pub const STIMULATOR_NODE_COST: f32 = 20.;
-#[derive(Component, Default)]
-#[require(SyltWorldScene)]
-pub struct StimulatorNode {
-    pub animation_timer: Timer,
-    pub click_timer: Timer,
-}
+pub fn spark_node_bundle(
+    kind: &str,
+    grid_position: GridPosition,
+) -> impl Bundle {
+    let mut animation_timer = Timer::from_seconds(0.25, TimerMode::Once);
+    animation_timer.pause();
 
-fn place_initial_node(mut cmd: Commands) {
-    cmd.spawn((
+    (
         StateScoped(SyltRouterState::Game),
-        SparkNode::default(),
-        ClickerNode::default(),
+        SparkNode {
+            kind: kind.to_string(),
+            animation_timer,
+            ..default()
+        },
         SyltWorldCanvasScaleFactor(1.0),
-        GridPosition::new(4, 4),
+        grid_position,
         Transform::from_xyz(0., 0., 100.),
-    ));
+    )
+}
+
+/// Keeps the entities of moved and swapped nodes instead of respawning them
+fn move_nodes(
+    mut simulation_events: EventReader<SimulationEvent>,
+    mut node_q: Query<&mut GridPosition, With<SparkNode>>,
+) {
+    for event in simulation_events.read() {
+        let moves = match event {
+            SimulationEvent::Moved { from, to } => vec![(*from, *to)],
+            SimulationEvent::Swapped { first, second } => {
+                vec![(*first, *second), (*second, *first)]
+            }
+            _ => continue,
+        };
+
+        // Both nodes of a swap are looked up before either of them moves
+        let entities: Vec<(Mut<GridPosition>, IVec2)> = node_q
+            .iter_mut()
+            .filter_map(|grid_position| {
+                let position = IVec2::new(grid_position.x, grid_position.y);
+                moves
+                    .iter()
+                    .find(|(from, _)| *from == position)
+                    .map(|(_, to)| (grid_position, *to))
+            })
+            .collect();
+
+        for (mut grid_position, to) in entities {
+            grid_position.x = to.x;
+            grid_position.y = to.y;
+        }
+    }
+}
+
+/// Spawns and despawns node entities to mirror the simulation grid
+fn sync_nodes(
+    mut cmd: Commands,
+    simulation: Res<SparkSimulation>,
+    node_q: Query<(Entity, &GridPosition, &SparkNode)>,
+) {
+    for (entity, grid_position, node) in &node_q {
+        let position = IVec2::new(grid_position.x, grid_position.y);
+
+        let in_simulation = simulation
+            .nodes
+            .get(&position)
+            .is_some_and(|simulation_node| simulation_node.kind == node.kind);
+
+        if !in_simulation {
+            cmd.entity(entity).despawn();
+        }
+    }
+
+    for (position, simulation_node) in &simulation.nodes {
+        let spawned = node_q.iter().any(|(_, grid_position, node)| {
+            grid_position.x == position.x
+                && grid_position.y == position.y
+                && node.kind == simulation_node.kind
+        });
+
+        if !spawned {
+            cmd.spawn(spark_node_bundle(
+                &simulation_node.kind,
+                GridPosition::new(position.x, position.y),
+            ));
+        }
+    }
 }
 
 /// positions nodes when GridPosition changes
@@ -103,17 +179,15 @@ fn position_node(
     }
 }
 
-fn animate_stimulator_node(
+fn animate_node(
     time: Res<Time>,
-    stimulator_q: Query<(
+    node_q: Query<(
         &mut SyltWorldCanvasScaleFactor,
-        &mut StimulatorNode,
+        &mut SparkNode,
         // &mut Transform,
-        // &SparkNode,
     )>,
 ) {
-    for (mut scale, mut node /* , mut transform, spark_node */) in stimulator_q
-    {
+    for (mut scale, mut node /* , mut transform */) in node_q {
         node.animation_timer.tick(time.delta());
         let v = node.animation_timer.fraction();
 
@@ -122,205 +196,236 @@ fn animate_stimulator_node(
         scale.0 = new_scale;
 
         // transform.translation.x =
-        //     spark_node.original_translation.x + 20. * new_scale;
+        //     node.original_translation.x + 20. * new_scale;
         // transform.translation.y =
-        //     spark_node.original_translation.y + 20. * new_scale;
-    }
-}
-
-fn draw_stimulator_node(
-    time: Res<Time>,
-    stimulator_q: Query<(&mut SyltWorldScene, &mut StimulatorNode)>,
-) {
-    for (mut scene, mut node) in stimulator_q {
-        let scene = &mut scene.inner;
-
-        scene.reset();
-
-        let pentagon =
-            plot_polygon_path((CELL_WIDTH * 0.5, CELL_HEIGHT * 0.5), 40., 5);
-
-        scene.fill(
-            vello::peniko::Fill::NonZero,
-            Default::default(),
-            vello::peniko::Color::new([0.3, 0.6, 0.9, 1.]),
-            None,
-            &pentagon,
-        );
-
-        // if let Some(timer) = node.animation_timer.as_mut() {
-        //     timer.tick(time.delta());
-        //     if timer.finished() {
-        //         node.animation_timer = None;
-        //     }
-        // }
-        //
-        // if let Some(timer) = node.animation_timer.as_mut() {
-        //     timer.tick(time.delta());
-        //     let v = timer.fraction();
-        //
-        //     let pentagon_enlarged = pentagon.to_path().to_path
-        //
-        // scene.fill(
-        //     vello::peniko::Fill::NonZero,
-        //     Default::default(),
-        //     vello::peniko::Color::new([0.3, 0.6, 0.9, 1.]),
-        //     None,
-        //     &pentagon_enlarged,
-        // );
-        // }
+        //     node.original_translation.y + 20. * new_scale;
     }
 }
 
-fn pulsate_stimulator_node(
+/// Animates nodes and plays sounds for everything the simulation reports
+fn on_simulation_event(
     mut cmd: Commands,
-    time: Res<Time>,
-    stimulator_node_q: Query<(&mut StimulatorNode, &GridPosition)>,
-    mut clicker_q: Query<(&GridPosition, &mut ClickerNode)>,
-    mut sparks: ResMut<Sparks>,
+    mut simulation_events: EventReader<SimulationEvent>,
+    mut node_triggered: EventReader<NodeTriggered>,
+    mut sparks_earned: EventReader<SparksEarned>,
+    node_q: Query<(&GridPosition, &mut SparkNode)>,
     sounds: Res<SyltSoundAssets>,
     settings: Res<SyltSettings>,
 ) {
-    let neighbors = [
-        Vec2::new(-1., -1.),
-        Vec2::new(0., -1.),
-        Vec2::new(1., -1.),
-        Vec2::new(-1., 0.),
-        Vec2::new(1., 0.),
-        Vec2::new(-1., 1.),
-        Vec2::new(0., 1.),
-        Vec2::new(1., 1.),
-    ];
-
-    for (mut node, stim_pos) in stimulator_node_q {
-        if node.click_timer.tick(time.delta()).finished() {
-            for (clicker_pos, mut clicker_node) in clicker_q.iter_mut() {
-                let result = Vec2::new(stim_pos.x as f32, stim_pos.y as f32)
-                    - Vec2::new(clicker_pos.x as f32, clicker_pos.y as f32);
-
-                if neighbors.contains(&result) {
+    let mut nodes: HashMap<IVec2, Mut<SparkNode>> = node_q
+        .into_iter()
+        .map(|(position, node)| (IVec2::new(position.x, position.y), node))
+        .collect();
+
+    for event in simulation_events.read() {
+        match event {
+            SimulationEvent::Pulsed { position, .. } => {
+                if let Some(node) = nodes.get_mut(position) {
                     node.animation_timer =
                         Timer::from_seconds(0.25, TimerMode::Once);
-                    clicker_node.animation_timer =
-                        Timer::from_seconds(0.067, TimerMode::Once);
+                }
 
-                    // TODO: delay with a timed event
-                    cmd.spawn(play_game_sound_despawn(
-                        sounds.pulsate.clone(),
-                        &settings,
-                    ));
-                    cmd.spawn(play_game_sound_despawn(
-                        sounds.click.clone(),
-                        &settings,
-                    ));
-                    sparks.0 += 1.;
+                cmd.spawn(play_game_sound_despawn(
+                    sounds.pulsate.clone(),
+                    &settings,
+                ));
+            }
+            SimulationEvent::Clicked { position } => {
+                if let Some(node) = nodes.get_mut(position) {
+                    node.animation_timer =
+                        Timer::from_seconds(0.067, TimerMode::Once);
                 }
             }
+            SimulationEvent::Built { .. }
+            | SimulationEvent::GridExpanded { .. }
+            | SimulationEvent::Sold { .. }
+            | SimulationEvent::Moved { .. }
+            | SimulationEvent::Swapped { .. }
+            | SimulationEvent::UpgradeBought { .. }
+            | SimulationEvent::Rotated { .. }
+            | SimulationEvent::Undone(_)
+            | SimulationEvent::Redone(_) => {}
         }
     }
+
+    for triggered in node_triggered.read() {
+        if let Some(node) = nodes.get_mut(&triggered.target) {
+            node.animation_timer = Timer::from_seconds(0.067, TimerMode::Once);
+        }
+    }
+
+    for earned in sparks_earned.read() {
+        if earned.yielded_at().is_some() {
+            cmd.spawn(play_game_sound_despawn(sounds.click.clone(), &settings));
+        }
+    }
+}
+
+/// Arrow from the middle of a cell towards a direction
+fn facing_arrow(facing: GridDirection) -> vello::kurbo::BezPath {
+    let center = vello::kurbo::Point::new(
+        CELL_WIDTH as f64 * 0.5,
+        CELL_HEIGHT as f64 * 0.5,
+    );
+    let offset = facing.offset().as_dvec2().normalize();
+    let direction = vello::kurbo::Vec2::new(offset.x, offset.y);
+    let side = vello::kurbo::Vec2::new(-direction.y, direction.x);
+    let length = CELL_WIDTH as f64 * 0.3;
+    let head = length * 0.5;
+
+    let tip = center + direction * length;
+    let mut path = vello::kurbo::BezPath::new();
+    path.move_to(center - direction * length);
+    path.line_to(tip);
+    path.move_to(tip - direction * head + side * head * 0.6);
+    path.line_to(tip);
+    path.line_to(tip - direction * head - side * head * 0.6);
+    path
 }
 
-fn draw_clicker_node(
+fn draw_node(
     mut cmd: Commands,
-    clicker_q: Query<(Entity, &mut SyltWorldScene, &ClickerNode)>,
+    node_q: Query<(Entity, &mut SyltWorldScene, &SparkNode, &GridPosition)>,
+    catalog: Res<NodeCatalog>,
+    simulation: Res<SparkSimulation>,
 ) {
-    for (entity, mut scene, _cell) in clicker_q {
-        let _size = scene.pixel_size;
+    for (entity, mut scene, node, grid_position) in node_q {
+        let Some(definition) = catalog.get(&node.kind) else {
+            continue;
+        };
+
         let scene = &mut scene.inner;
 
         scene.reset();
 
-        let shape = vello::kurbo::Circle::new(
-            (CELL_WIDTH * 0.5, CELL_HEIGHT * 0.5),
-            40.,
-        );
+        let shape = definition
+            .shape
+            .to_path((CELL_WIDTH as f64 * 0.5, CELL_HEIGHT as f64 * 0.5));
 
-        cmd.entity(entity).insert(SyltPickingShape {
-            inner: shape.to_path(0.1),
-            ..default()
-        });
+        // Only nodes that yield sparks can be clicked
+        if !definition.yields.is_zero() {
+            cmd.entity(entity).insert(SyltPickingShape {
+                inner: shape.clone(),
+                ..default()
+            });
+        }
 
         scene.fill(
             vello::peniko::Fill::NonZero,
             vello::kurbo::Affine::default(),
-            vello::peniko::Color::new([0.3, 0.6, 0.9, 1.]),
+            definition.color(),
             None,
             &shape,
         );
-    }
-}
 
-fn animate_clicker_node(
-    time: Res<Time>,
-    stimulator_q: Query<(
-        &mut SyltWorldCanvasScaleFactor,
-        &mut ClickerNode,
-        // &mut Transform,
-        // &SparkNode,
-    )>,
-) {
-    for (mut scale, mut node /* , mut transform, spark_node */) in stimulator_q
-    {
-        node.animation_timer.tick(time.delta());
-        let v = node.animation_timer.fraction();
+        if !definition.trigger_pattern.is_directional() {
+            continue;
+        }
 
-        // TODO: center scaling
-        let new_scale = bump_logistic(v, 10.).remap(0., 1., 1., 1.2);
-        scale.0 = new_scale;
+        let Some(simulation_node) = simulation
+            .nodes
+            .get(&IVec2::new(grid_position.x, grid_position.y))
+        else {
+            continue;
+        };
 
-        // transform.translation.x =
-        //     spark_node.original_translation.x + 20. * new_scale;
-        // transform.translation.y =
-        //     spark_node.original_translation.y + 20. * new_scale;
+        scene.stroke(
+            &vello::kurbo::Stroke::new(4.),
+            vello::kurbo::Affine::default(),
+            vello::peniko::Color::WHITE,
+            None,
+            &facing_arrow(simulation_node.facing),
+        );
     }
 }
 
+/// Focuses the released node and clicks it, while carrying a node the
+/// release only picks the cell
 fn on_pointer_release(
     trigger: Trigger<Pointer<Released>>,
     mut cmd: Commands,
-    clicker_q: Query<&ClickerNode>,
-    mut sparks: ResMut<Sparks>,
-    sounds: Res<SyltSoundAssets>,
-    settings: Res<SyltSettings>,
+    node_q: Query<&GridPosition, With<SparkNode>>,
+    display_shop: Option<Res<ShowBuildMenu>>,
+    node_menu: Option<Res<NodeMenu>>,
+    upgrade_panel: Option<Res<UpgradePanel>>,
+    simulation: Option<Res<SparkSimulation>>,
+    mut simulation_actions: EventWriter<SimulationAction>,
 ) {
-    if clicker_q.get(trigger.target).is_ok() {
-        cmd.spawn(play_game_sound_despawn(sounds.click.clone(), &settings));
-        sparks.0 += 1.;
+    if trigger.button != PointerButton::Primary {
+        return;
+    }
+
+    let Ok(grid_position) = node_q.get(trigger.target) else {
+        return;
+    };
+
+    let (Some(display_shop), Some(node_menu), Some(upgrade_panel), Some(_)) =
+        (display_shop, node_menu, upgrade_panel, simulation)
+    else {
+        return;
+    };
+
+    if display_shop.0 || *node_menu == NodeMenu::Open || upgrade_panel.open {
+        return;
+    }
+
+    // The node covers its cell, so it moves the focus in its place
+    cmd.insert_resource(FocusedGridPosition::new(
+        grid_position.x,
+        grid_position.y,
+    ));
+
+    if *node_menu != NodeMenu::Closed {
+        return;
     }
+
+    let position = IVec2::new(grid_position.x, grid_position.y);
+
+    simulation_actions.write(SimulationAction::Click { position });
 }
 
 fn on_keyboard_press(
-    mut cmd: Commands,
     display_shop: Res<ShowBuildMenu>,
+    node_menu: Res<NodeMenu>,
+    upgrade_panel: Res<UpgradePanel>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut sparks: ResMut<Sparks>,
-    clicker_q: Query<(&GridPosition, &mut ClickerNode)>,
+    simulation: Res<SparkSimulation>,
+    mut simulation_actions: EventWriter<SimulationAction>,
     focused_grid_position: Res<FocusedGridPosition>,
-    sounds: Res<SyltSoundAssets>,
-    settings: Res<SyltSettings>,
 ) {
-    if display_shop.0 {
+    if display_shop.0 || *node_menu != NodeMenu::Closed || upgrade_panel.open {
         return;
     }
 
+    // Using ButtonInput should prevent holding the key down
     if keyboard_input.any_just_pressed([
         KeyCode::Space,
         KeyCode::Enter,
         KeyCode::KeyI,
     ]) {
-        for (grid_position, mut node) in clicker_q {
-            if grid_position.x == focused_grid_position.x
-                && grid_position.y == focused_grid_position.y
-            {
-                node.animation_timer =
-                    Timer::from_seconds(0.067, TimerMode::Once);
-                // Using ButtonInput should prevent holding the key down
-                cmd.spawn(play_game_sound_despawn(
-                    sounds.click.clone(),
-                    &settings,
-                ));
-                sparks.0 += 1.;
-            }
+        let position =
+            IVec2::new(focused_grid_position.x, focused_grid_position.y);
+
+        if !simulation.nodes.contains_key(&position) {
+            return;
         }
+
+        simulation_actions.write(SimulationAction::Click { position });
     }
+
+    // Q and E turn directional nodes counter clockwise and clockwise
+    let clockwise = if keyboard_input.just_pressed(KeyCode::KeyE) {
+        true
+    } else if keyboard_input.just_pressed(KeyCode::KeyQ) {
+        false
+    } else {
+        return;
+    };
+
+    let position = IVec2::new(focused_grid_position.x, focused_grid_position.y);
+
+    simulation_actions.write(SimulationAction::Rotate {
+        position,
+        clockwise,
+    });
 }
@@ -0,0 +1,170 @@
+use bevy::{prelude::*, render::view::RenderLayers, ui::ContentSize};
+
+use crate::{
+    canvas::{
+        text::{SyltText, SyltTextAlign, SyltTextStyle},
+        ui_canvas::SyltUiText,
+    },
+    game::{currency::SparkAmount, system_set::SyltPausableSystems},
+    i18n::SyltI18nText,
+    routes::SyltRouterState,
+    settings::SyltSettings,
+    ui::constants::SU4,
+};
+
+/// Seconds the "while you were away" summary stays on screen
+const SUMMARY_DURATION: f32 = 5.;
+
+pub struct OfflinePlugin;
+
+impl Plugin for OfflinePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnExit(SyltRouterState::Game), remove_offline_progress);
+
+        app.add_systems(
+            Update,
+            (
+                spawn_offline_summary.run_if(resource_added::<OfflineProgress>),
+                despawn_offline_summary,
+            )
+                .in_set(SyltPausableSystems)
+                .run_if(in_state(SyltRouterState::Game)),
+        );
+    }
+}
+
+/// Progress simulated for the time the game was closed
+#[derive(Resource)]
+pub struct OfflineProgress {
+    pub seconds: f32,
+    pub sparks: SparkAmount,
+}
+
+/// Seconds between saving and now, capped to the configured offline hours.
+/// Clocks that moved backwards yield no progress.
+pub fn offline_seconds(saved_at: i64, now: i64, max_hours: f32) -> f32 {
+    if saved_at <= 0 {
+        return 0.;
+    }
+
+    let elapsed = (now - saved_at).max(0) as f32;
+    elapsed.min(max_hours.max(0.) * 3600.)
+}
+
+#[derive(Component)]
+struct OfflineSummary(pub Timer);
+
+fn remove_offline_progress(mut cmd: Commands) {
+    cmd.remove_resource::<OfflineProgress>();
+}
+
+fn spawn_offline_summary(
+    mut cmd: Commands,
+    offline_progress: Res<OfflineProgress>,
+    settings: Res<SyltSettings>,
+) {
+    let minutes = (offline_progress.seconds / 60.).floor() as u32;
+
+    cmd.spawn((
+        StateScoped(SyltRouterState::Game),
+        OfflineSummary(Timer::from_seconds(SUMMARY_DURATION, TimerMode::Once)),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.),
+            top: Val::Px(SU4 * 4.),
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(SU4),
+            ..default()
+        },
+        Pickable::IGNORE,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            RenderLayers::layer(1),
+            SyltUiText,
+            SyltTextStyle {
+                font_size: 28.,
+                ..default()
+            },
+            SyltTextAlign::Middle,
+            SyltText::default(),
+            SyltI18nText::from_key("while you were away"),
+            Node::default(),
+            ContentSize::default(),
+        ));
+
+        parent.spawn((
+            RenderLayers::layer(1),
+            SyltUiText,
+            SyltTextStyle {
+                font_size: 28.,
+                ..default()
+            },
+            SyltTextAlign::Middle,
+            SyltText {
+                content: format!(
+                    "+{} SP ({}h {}m)",
+                    offline_progress.sparks.format(settings.number_notation.0),
+                    minutes / 60,
+                    minutes % 60,
+                ),
+                ..default()
+            },
+            Node::default(),
+            ContentSize::default(),
+        ));
+    });
+}
+
+/// Ticks with real time, the summary is not part of the game
+fn despawn_offline_summary(
+    mut cmd: Commands,
+    time: Res<Time<Real>>,
+    summary_q: Query<(Entity, &mut OfflineSummary)>,
+) {
+    for (entity, mut summary) in summary_q {
+        if summary.0.tick(time.delta()).finished() {
+            cmd.entity(entity).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAVED_AT: i64 = 1_700_000_000;
+
+    #[test]
+    fn seconds_since_saving() {
+        assert_eq!(offline_seconds(SAVED_AT, SAVED_AT + 90, 24.), 90.);
+        assert_eq!(offline_seconds(SAVED_AT, SAVED_AT, 24.), 0.);
+    }
+
+    #[test]
+    fn capped_to_the_offline_hours() {
+        let two_days = SAVED_AT + 48 * 3600;
+
+        assert_eq!(offline_seconds(SAVED_AT, two_days, 24.), 24. * 3600.);
+        assert_eq!(offline_seconds(SAVED_AT, two_days, 1.), 3600.);
+    }
+
+    #[test]
+    fn no_progress_when_turned_off() {
+        assert_eq!(offline_seconds(SAVED_AT, SAVED_AT + 3600, 0.), 0.);
+        assert_eq!(offline_seconds(SAVED_AT, SAVED_AT + 3600, -1.), 0.);
+    }
+
+    #[test]
+    fn no_progress_when_the_clock_moved_backwards() {
+        assert_eq!(offline_seconds(SAVED_AT, SAVED_AT - 3600, 24.), 0.);
+    }
+
+    #[test]
+    fn no_progress_without_a_save_time() {
+        assert_eq!(offline_seconds(0, SAVED_AT, 24.), 0.);
+        assert_eq!(offline_seconds(-5, SAVED_AT, 24.), 0.);
+    }
+}
@@ -0,0 +1,136 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{
+    canvas::{
+        text::{SyltText, SyltTextAlign, SyltTextAnchor, SyltTextStyle},
+        world_canvas::SyltWorldText,
+    },
+    game::{
+        currency::SparkAmount,
+        grid::{CELL_GAP, CELL_HEIGHT, CELL_WIDTH},
+        simulation::{SparkSimulation, SparksEarned},
+        system_set::{SyltGameSystemSet, SyltPausableSystems},
+    },
+    routes::SyltRouterState,
+    settings::SyltSettings,
+    vectors::easings::{ease_in_cubic, ease_out_cubic},
+};
+
+/// Seconds a popup takes to rise and fade out
+const POPUP_DURATION: f32 = 0.8;
+/// Distance a popup rises over its lifetime
+const POPUP_RISE: f32 = 40.;
+/// Popups alive at once, gains beyond this are not shown
+const MAX_POPUPS: usize = 48;
+
+pub struct PopupsPlugin;
+
+impl Plugin for PopupsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (spawn_popups, animate_popups)
+                .chain()
+                .in_set(SyltPausableSystems)
+                .in_set(SyltGameSystemSet::Update)
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<SparkSimulation>),
+                ),
+        );
+    }
+}
+
+/// A floating spark gain above a node
+#[derive(Component)]
+struct SparkPopup {
+    timer: Timer,
+    origin: Vec3,
+}
+
+fn spawn_popups(
+    mut cmd: Commands,
+    mut sparks_earned: EventReader<SparksEarned>,
+    popup_q: Query<(), With<SparkPopup>>,
+    settings: Res<SyltSettings>,
+) {
+    // Gains of the same node in one frame share a popup
+    let mut gains: HashMap<IVec2, SparkAmount> = HashMap::default();
+    let mut order = Vec::new();
+
+    for earned in sparks_earned.read() {
+        let Some(position) = earned.yielded_at() else {
+            continue;
+        };
+
+        let gain = gains.entry(position).or_insert_with(|| {
+            order.push(position);
+            SparkAmount::ZERO
+        });
+        *gain += earned.amount;
+    }
+
+    let free = MAX_POPUPS.saturating_sub(popup_q.iter().count());
+
+    for position in order.into_iter().take(free) {
+        let Some(amount) = gains.get(&position) else {
+            continue;
+        };
+
+        // Top middle of the cell, above the nodes
+        let origin = Vec3::new(
+            position.x as f32 * (CELL_GAP + CELL_WIDTH) + CELL_WIDTH * 0.5,
+            -(position.y as f32) * (CELL_GAP + CELL_HEIGHT),
+            500.,
+        );
+
+        cmd.spawn((
+            StateScoped(SyltRouterState::Game),
+            SparkPopup {
+                timer: Timer::from_seconds(POPUP_DURATION, TimerMode::Once),
+                origin,
+            },
+            SyltWorldText,
+            SyltText {
+                content: format!(
+                    "+{}",
+                    amount.format(settings.number_notation.0)
+                ),
+                ..default()
+            },
+            SyltTextStyle {
+                font_size: 20.,
+                ..default()
+            },
+            SyltTextAnchor::Center,
+            SyltTextAlign::Middle,
+            Transform::from_translation(origin),
+        ));
+    }
+}
+
+fn animate_popups(
+    mut cmd: Commands,
+    time: Res<Time>,
+    popup_q: Query<(
+        Entity,
+        &mut SparkPopup,
+        &mut Transform,
+        &mut SyltTextStyle,
+    )>,
+) {
+    for (entity, mut popup, mut transform, mut style) in popup_q {
+        if popup.timer.tick(time.delta()).finished() {
+            cmd.entity(entity).despawn();
+            continue;
+        }
+
+        let t = popup.timer.fraction();
+
+        transform.translation.y =
+            popup.origin.y + POPUP_RISE * ease_out_cubic(t);
+        style.brush = vello::peniko::Brush::Solid(
+            vello::peniko::Color::WHITE.with_alpha(1. - ease_in_cubic(t)),
+        );
+    }
+}
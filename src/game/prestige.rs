@@ -0,0 +1,236 @@
+use bevy::prelude::*;
+
+use crate::{
+    game::{
+        catalog::NodeCatalog,
+        currency::SparkAmount,
+        grid::FocusedGridPosition,
+        save::GameStart,
+        simulation::{SparkSimulation, INITIAL_POSITION},
+        upgrades::UpgradeEffect,
+    },
+    routes::SyltRouterState,
+    signals::SyltSignal,
+};
+
+const PRESTIGE_FILE: &str = "prestige.yaml";
+/// Lifetime sparks needed for the first ember, the reward grows with the
+/// square root of the sparks earned
+const EMBERS_BASE: f64 = 1000.;
+
+pub struct PrestigePlugin;
+
+impl Plugin for PrestigePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PrestigeEvent>();
+        app.init_resource::<PrestigeProgress>();
+
+        app.add_systems(Startup, trigger_load_prestige);
+
+        app.add_systems(Update, (handle_prestige_loaded, buy_bonus));
+
+        app.add_systems(
+            Update,
+            (prestige).run_if(
+                in_state(SyltRouterState::Game)
+                    .and(resource_exists::<SparkSimulation>),
+            ),
+        );
+    }
+}
+
+#[derive(Event, Debug, Clone, PartialEq)]
+pub enum PrestigeEvent {
+    /// Trade the running game for embers
+    Reset,
+    /// Spend embers on a permanent bonus
+    BuyBonus(String),
+}
+
+/// A permanent bonus, as described in `assets/nodes.ron`
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct PrestigeBonusDefinition {
+    pub id: String,
+    pub i18n_key: String,
+    pub description_key: String,
+    /// Price in embers
+    pub cost: u64,
+    pub effects: Vec<UpgradeEffect>,
+}
+
+/// Everything that survives a prestige reset, saved separately from the
+/// game so starting a new game keeps it.
+#[derive(
+    Resource, Debug, Clone, Default, serde::Deserialize, serde::Serialize,
+)]
+pub struct PrestigeProgress {
+    pub embers: u64,
+    pub resets: u32,
+    /// Ids of the bought bonuses
+    pub bonuses: Vec<String>,
+}
+
+impl PrestigeProgress {
+    /// Effects of the bought bonuses, applied to every game
+    pub fn effects(&self, catalog: &NodeCatalog) -> Vec<UpgradeEffect> {
+        catalog
+            .prestige_bonuses
+            .iter()
+            .filter(|bonus| self.bonuses.contains(&bonus.id))
+            .flat_map(|bonus| bonus.effects.iter().cloned())
+            .collect()
+    }
+}
+
+/// Embers earned by resetting after earning `lifetime` sparks
+pub fn embers_for(lifetime: SparkAmount) -> u64 {
+    if lifetime.is_zero() {
+        return 0;
+    }
+
+    // sqrt(lifetime / base) in log space, lifetimes can exceed an f64
+    let log = (lifetime.log10() - EMBERS_BASE.log10()) / 2.;
+    if log < 0. {
+        return 0;
+    }
+
+    10f64.powf(log).floor().min(u64::MAX as f64) as u64
+}
+
+fn write_progress(
+    progress: &PrestigeProgress,
+    sylt_signal_writer: &mut EventWriter<SyltSignal>,
+) {
+    match serde_yaml::to_string(progress) {
+        Ok(data) => {
+            sylt_signal_writer.write(SyltSignal::SaveFile {
+                key: PRESTIGE_FILE.into(),
+                data: data.into(),
+            });
+        }
+        Err(e) => warn!("Could not serialize the prestige progress: {e}"),
+    }
+}
+
+/// Starts the board over and hands out embers, then opens the prestige shop.
+/// Leaving the game saves the fresh board. Scenarios can not be traded in.
+fn prestige(
+    mut cmd: Commands,
+    game_start: Res<GameStart>,
+    mut prestige_reader: EventReader<PrestigeEvent>,
+    mut progress: ResMut<PrestigeProgress>,
+    mut simulation: ResMut<SparkSimulation>,
+    mut sylt_signal_writer: EventWriter<SyltSignal>,
+    mut router_state: ResMut<NextState<SyltRouterState>>,
+) {
+    if !prestige_reader
+        .read()
+        .any(|event| *event == PrestigeEvent::Reset)
+    {
+        return;
+    }
+
+    if matches!(*game_start, GameStart::Scenario(_)) {
+        debug!("Can not prestige during a scenario");
+        return;
+    }
+
+    let embers = embers_for(simulation.lifetime_sparks);
+    if embers == 0 {
+        debug!("Not enough sparks earned to prestige");
+        return;
+    }
+
+    progress.embers = progress.embers.saturating_add(embers);
+    progress.resets += 1;
+    write_progress(&progress, &mut sylt_signal_writer);
+
+    simulation.start_new();
+    cmd.insert_resource(FocusedGridPosition {
+        x: INITIAL_POSITION.x,
+        y: INITIAL_POSITION.y,
+    });
+
+    router_state.set(SyltRouterState::Prestige);
+}
+
+fn buy_bonus(
+    mut prestige_reader: EventReader<PrestigeEvent>,
+    mut progress: ResMut<PrestigeProgress>,
+    catalog: Res<NodeCatalog>,
+    mut sylt_signal_writer: EventWriter<SyltSignal>,
+) {
+    for event in prestige_reader.read() {
+        let PrestigeEvent::BuyBonus(id) = event else {
+            continue;
+        };
+
+        let Some(bonus) = catalog
+            .prestige_bonuses
+            .iter()
+            .find(|bonus| bonus.id == *id)
+        else {
+            debug!("Unknown prestige bonus: {id}");
+            continue;
+        };
+
+        if progress.bonuses.contains(id) || progress.embers < bonus.cost {
+            continue;
+        }
+
+        progress.embers -= bonus.cost;
+        progress.bonuses.push(id.clone());
+        write_progress(&progress, &mut sylt_signal_writer);
+    }
+}
+
+fn trigger_load_prestige(mut sylt_signal_writer: EventWriter<SyltSignal>) {
+    sylt_signal_writer.write(SyltSignal::LoadFile {
+        key: PRESTIGE_FILE.into(),
+    });
+}
+
+fn handle_prestige_loaded(
+    mut sylt_signal_reader: EventReader<SyltSignal>,
+    mut progress: ResMut<PrestigeProgress>,
+) {
+    for event in sylt_signal_reader.read() {
+        if let SyltSignal::FileLoaded { key, data } = event {
+            if key.to_string() != PRESTIGE_FILE {
+                continue;
+            }
+
+            match serde_yaml::from_str::<PrestigeProgress>(data) {
+                Ok(loaded) => *progress = loaded,
+                Err(e) => warn!("Could not parse the prestige progress: {e}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_embers_without_sparks() {
+        assert_eq!(embers_for(SparkAmount::ZERO), 0);
+        assert_eq!(embers_for(SparkAmount::from(1.)), 0);
+        assert_eq!(embers_for(SparkAmount::from(999.)), 0);
+    }
+
+    #[test]
+    fn embers_grow_with_the_square_root() {
+        assert_eq!(embers_for(SparkAmount::from(1000.)), 1);
+        assert_eq!(embers_for(SparkAmount::from(4000.)), 2);
+        assert_eq!(embers_for(SparkAmount::from(1e5)), 10);
+        assert_eq!(embers_for(SparkAmount::from(1e6)), 31);
+        assert_eq!(embers_for(SparkAmount::from(1e9)), 1000);
+    }
+
+    #[test]
+    fn embers_cap_at_the_largest_count() {
+        assert_eq!(embers_for(SparkAmount::from_log10(1000.)), u64::MAX);
+        assert_eq!(embers_for(SparkAmount::MAX), u64::MAX);
+    }
+}
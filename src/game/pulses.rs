@@ -0,0 +1,97 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+
+/// Seconds between a node being triggered and the pulse reaching the next
+/// node, unless the node type sets its own delay
+pub const PULSE_HOP_DELAY: f32 = 0.15;
+/// Seconds before a node can re-emit pulses again
+pub const NODE_COOLDOWN: f32 = 0.5;
+/// Chains stop propagating after this many hops
+pub const MAX_CHAIN_DEPTH: u32 = 16;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pulse {
+    pub source: IVec2,
+    /// Type of the node that sent the pulse, it may be gone when the pulse
+    /// arrives
+    pub source_kind: String,
+    pub target: IVec2,
+    /// Scheduler time in seconds at which the pulse reaches the target
+    pub fire_at: f32,
+    /// Number of hops since the pulsing node that started the chain
+    pub depth: u32,
+}
+
+/// Queues pulses travelling between nodes so chain reactions ripple across
+/// the grid instead of resolving in a single frame.
+#[derive(Default)]
+pub struct PulseScheduler {
+    pub elapsed: f32,
+    pub queue: Vec<Pulse>,
+    /// Scheduler time at which a node is allowed to re-emit again
+    pub cooldowns: HashMap<IVec2, f32>,
+    pub deepest_chain: u32,
+}
+
+impl PulseScheduler {
+    /// Queues pulses that reach the targets `delay` seconds from now
+    pub fn schedule(
+        &mut self,
+        source: IVec2,
+        source_kind: &str,
+        targets: &[IVec2],
+        depth: u32,
+        delay: f32,
+    ) {
+        if depth > MAX_CHAIN_DEPTH {
+            return;
+        }
+
+        let fire_at = self.elapsed + delay;
+
+        for target in targets {
+            self.queue.push(Pulse {
+                source,
+                source_kind: source_kind.to_string(),
+                target: *target,
+                fire_at,
+                depth,
+            });
+        }
+    }
+
+    /// Starts the cooldown of a node, returns false if the node is still
+    /// cooling down and should not re-emit.
+    pub fn try_cooldown(&mut self, position: IVec2) -> bool {
+        if self
+            .cooldowns
+            .get(&position)
+            .is_some_and(|ready_at| *ready_at > self.elapsed)
+        {
+            return false;
+        }
+
+        self.cooldowns
+            .insert(position, self.elapsed + NODE_COOLDOWN);
+        true
+    }
+
+    /// Advances the scheduler and returns the pulses that are due
+    pub fn advance(&mut self, delta: f32) -> Vec<Pulse> {
+        self.elapsed += delta;
+
+        let elapsed = self.elapsed;
+        let (due, pending): (Vec<Pulse>, Vec<Pulse>) = self
+            .queue
+            .drain(..)
+            .partition(|pulse| pulse.fire_at <= elapsed);
+
+        self.queue = pending;
+        self.cooldowns.retain(|_, ready_at| *ready_at > elapsed);
+
+        for pulse in &due {
+            self.deepest_chain = self.deepest_chain.max(pulse.depth);
+        }
+
+        due
+    }
+}
@@ -0,0 +1,350 @@
+use bevy::{
+    ecs::system::EntityCommands, prelude::*, window::WindowCloseRequested,
+};
+
+use crate::{
+    game::{
+        catalog::GridDirection,
+        currency::SparkAmount,
+        grid::{FocusedGridPosition, GridBounds},
+        simulation::SparkSimulation,
+        statistics::{GameStatistics, Statistics},
+        system_set::{SyltGamePauseState, SyltPausableSystems},
+        Sparks,
+    },
+    routes::SyltRouterState,
+    signals::SyltSignal,
+    ui::components::button::SyltButtonPressed,
+};
+
+const SAVE_FILE: &str = "save.yaml";
+/// Seconds between autosaves while playing
+const AUTOSAVE_INTERVAL: f32 = 30.;
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SaveGameEvent>();
+        app.init_resource::<SavedGame>();
+        app.init_resource::<GameStart>();
+
+        app.add_systems(Startup, trigger_load_game);
+
+        app.add_systems(OnEnter(SyltRouterState::Game), insert_autosave_timer);
+
+        app.add_systems(
+            OnExit(SyltRouterState::Game),
+            (save_game_on_exit, remove_autosave_timer, reset_game_start)
+                .chain(),
+        );
+
+        app.add_systems(
+            OnEnter(SyltGamePauseState(true)),
+            send_save_game_event.run_if(in_state(SyltRouterState::Game)),
+        );
+
+        app.add_systems(Update, (handle_game_loaded, handle_game_saved));
+
+        app.add_systems(
+            Update,
+            (autosave).in_set(SyltPausableSystems).run_if(
+                in_state(SyltRouterState::Game)
+                    .and(resource_exists::<AutosaveTimer>),
+            ),
+        );
+
+        app.add_systems(
+            Update,
+            (save_game_on_window_close, trigger_save_game)
+                .chain()
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<SparkSimulation>)
+                        .and(resource_exists::<FocusedGridPosition>),
+                ),
+        );
+    }
+}
+
+/// Requests the running game to be written to storage
+#[derive(Event)]
+pub struct SaveGameEvent;
+
+/// Whether entering the game starts a new board, restores [`SavedGame`] or
+/// plays a scenario
+#[derive(Resource, Default, PartialEq)]
+pub enum GameStart {
+    #[default]
+    New,
+    Continue,
+    /// Id of the scenario, scenario games are never saved
+    Scenario(String),
+}
+
+/// The most recent snapshot, loaded from storage on startup and kept up to
+/// date with every save.
+#[derive(Resource, Default)]
+pub struct SavedGame(pub Option<GameSnapshot>);
+
+impl SavedGame {
+    /// The snapshot to restore when entering the game, if any
+    pub fn to_restore(&self, game_start: &GameStart) -> Option<&GameSnapshot> {
+        match game_start {
+            GameStart::New | GameStart::Scenario(_) => None,
+            GameStart::Continue => self.0.as_ref(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct NodeSnapshot {
+    pub kind: String,
+    pub x: i32,
+    pub y: i32,
+    pub pulse_elapsed: f32,
+    #[serde(default)]
+    pub facing: GridDirection,
+}
+
+/// Serializable game state. Pulses still travelling between nodes are not
+/// saved, they resolve within a fraction of a second anyway.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct GameSnapshot {
+    /// Unix timestamp in seconds of when the snapshot was taken
+    #[serde(default)]
+    pub saved_at: i64,
+    pub sparks: SparkAmount,
+    #[serde(default)]
+    pub lifetime_sparks: SparkAmount,
+    #[serde(default)]
+    pub bounds: GridBounds,
+    pub nodes: Vec<NodeSnapshot>,
+    /// Ids of the bought upgrades
+    #[serde(default)]
+    pub upgrades: Vec<String>,
+    /// Statistics of this game, lifetime statistics are saved on their own
+    #[serde(default)]
+    pub statistics: Statistics,
+    pub focused_x: i32,
+    pub focused_y: i32,
+}
+
+impl GameSnapshot {
+    pub fn capture(
+        simulation: &SparkSimulation,
+        focused_grid_position: &FocusedGridPosition,
+        statistics: &GameStatistics,
+    ) -> Self {
+        let mut nodes: Vec<NodeSnapshot> = simulation
+            .nodes
+            .iter()
+            .map(|(position, node)| NodeSnapshot {
+                kind: node.kind.clone(),
+                x: position.x,
+                y: position.y,
+                pulse_elapsed: node.pulse_elapsed,
+                facing: node.facing,
+            })
+            .collect();
+        nodes.sort_by_key(|node| (node.y, node.x));
+
+        Self {
+            saved_at: chrono::Utc::now().timestamp(),
+            sparks: simulation.sparks.0,
+            lifetime_sparks: simulation.lifetime_sparks,
+            bounds: simulation.bounds,
+            nodes,
+            upgrades: simulation.purchased().to_vec(),
+            statistics: statistics.game.clone(),
+            focused_x: focused_grid_position.x,
+            focused_y: focused_grid_position.y,
+        }
+    }
+
+    pub fn restore(&self, simulation: &mut SparkSimulation) {
+        simulation.sparks = Sparks(self.sparks);
+        simulation.lifetime_sparks = self.lifetime_sparks;
+        simulation.bounds = self.bounds;
+        simulation.set_purchased(self.upgrades.clone());
+        simulation.nodes.clear();
+
+        for node in &self.nodes {
+            let position = IVec2::new(node.x, node.y);
+            simulation.insert_node(&node.kind, position);
+
+            if let Some(restored) = simulation.nodes.get_mut(&position) {
+                restored.pulse_elapsed = node.pulse_elapsed;
+                restored.facing = node.facing;
+            }
+        }
+    }
+}
+
+pub trait SyltContinueGameExt {
+    /// Restores the saved game when the button navigates into the game
+    fn continue_on_click(&mut self) -> &mut Self;
+}
+
+impl SyltContinueGameExt for EntityCommands<'_> {
+    fn continue_on_click(&mut self) -> &mut Self {
+        self.observe(
+            |_: Trigger<SyltButtonPressed>,
+             mut game_start: ResMut<GameStart>| {
+                *game_start = GameStart::Continue;
+            },
+        )
+    }
+}
+
+#[derive(Resource)]
+struct AutosaveTimer(pub Timer);
+
+fn insert_autosave_timer(mut cmd: Commands) {
+    cmd.insert_resource(AutosaveTimer(Timer::from_seconds(
+        AUTOSAVE_INTERVAL,
+        TimerMode::Repeating,
+    )));
+}
+
+fn remove_autosave_timer(mut cmd: Commands) {
+    cmd.remove_resource::<AutosaveTimer>();
+}
+
+fn reset_game_start(mut game_start: ResMut<GameStart>) {
+    *game_start = GameStart::New;
+}
+
+fn send_save_game_event(mut save_game_writer: EventWriter<SaveGameEvent>) {
+    save_game_writer.write(SaveGameEvent);
+}
+
+/// Counts real time, a paused or sped up game still saves every interval
+fn autosave(
+    time: Res<Time<Real>>,
+    mut autosave_timer: ResMut<AutosaveTimer>,
+    mut save_game_writer: EventWriter<SaveGameEvent>,
+) {
+    if autosave_timer.0.tick(time.delta()).just_finished() {
+        save_game_writer.write(SaveGameEvent);
+    }
+}
+
+fn save_game_on_window_close(
+    mut window_close_reader: EventReader<WindowCloseRequested>,
+    mut save_game_writer: EventWriter<SaveGameEvent>,
+) {
+    if window_close_reader.read().count() > 0 {
+        save_game_writer.write(SaveGameEvent);
+    }
+}
+
+fn write_snapshot(
+    snapshot: GameSnapshot,
+    sylt_signal_writer: &mut EventWriter<SyltSignal>,
+    saved_game: &mut SavedGame,
+) {
+    match serde_yaml::to_string(&snapshot) {
+        Ok(data) => {
+            sylt_signal_writer.write(SyltSignal::SaveFile {
+                key: SAVE_FILE.into(),
+                data: data.into(),
+            });
+            saved_game.0 = Some(snapshot);
+        }
+        Err(e) => warn!("Could not serialize the game: {e}"),
+    }
+}
+
+fn trigger_save_game(
+    mut save_game_reader: EventReader<SaveGameEvent>,
+    mut sylt_signal_writer: EventWriter<SyltSignal>,
+    mut saved_game: ResMut<SavedGame>,
+    simulation: Res<SparkSimulation>,
+    focused_grid_position: Res<FocusedGridPosition>,
+    statistics: Res<GameStatistics>,
+    game_start: Res<GameStart>,
+) {
+    // Several requests in the same frame result in a single save
+    if save_game_reader.read().count() == 0
+        || matches!(*game_start, GameStart::Scenario(_))
+    {
+        return;
+    }
+
+    write_snapshot(
+        GameSnapshot::capture(&simulation, &focused_grid_position, &statistics),
+        &mut sylt_signal_writer,
+        &mut saved_game,
+    );
+}
+
+/// Leaving the game removes the simulation, so it is saved right away
+/// instead of through a [`SaveGameEvent`]
+fn save_game_on_exit(
+    mut sylt_signal_writer: EventWriter<SyltSignal>,
+    mut saved_game: ResMut<SavedGame>,
+    simulation: Option<Res<SparkSimulation>>,
+    focused_grid_position: Option<Res<FocusedGridPosition>>,
+    statistics: Res<GameStatistics>,
+    game_start: Res<GameStart>,
+) {
+    let (Some(simulation), Some(focused_grid_position)) =
+        (simulation, focused_grid_position)
+    else {
+        return;
+    };
+
+    if matches!(*game_start, GameStart::Scenario(_)) {
+        return;
+    }
+
+    write_snapshot(
+        GameSnapshot::capture(&simulation, &focused_grid_position, &statistics),
+        &mut sylt_signal_writer,
+        &mut saved_game,
+    );
+}
+
+fn trigger_load_game(mut sylt_signal_writer: EventWriter<SyltSignal>) {
+    sylt_signal_writer.write(SyltSignal::LoadFile {
+        key: SAVE_FILE.into(),
+    });
+}
+
+fn handle_game_loaded(
+    mut sylt_signal_reader: EventReader<SyltSignal>,
+    mut saved_game: ResMut<SavedGame>,
+) {
+    for event in sylt_signal_reader.read() {
+        if let SyltSignal::FileLoaded { key, data } = event {
+            if key.to_string() != SAVE_FILE {
+                continue;
+            }
+
+            match serde_yaml::from_str::<GameSnapshot>(data) {
+                Ok(snapshot) => {
+                    debug!("Game loaded");
+                    saved_game.0 = Some(snapshot);
+                }
+                Err(e) => warn!("Could not parse the saved game: {e}"),
+            }
+        }
+    }
+}
+
+fn handle_game_saved(mut sylt_signal_reader: EventReader<SyltSignal>) {
+    for event in sylt_signal_reader.read() {
+        match event {
+            SyltSignal::FileSaved { key } if key.to_string() == SAVE_FILE => {
+                debug!("Game saved");
+            }
+            SyltSignal::SaveFileError { key, message }
+                if key.to_string() == SAVE_FILE =>
+            {
+                warn!("Could not save the game: {message}");
+            }
+            _ => {}
+        }
+    }
+}
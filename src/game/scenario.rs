@@ -0,0 +1,787 @@
+use std::collections::BTreeMap;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+    render::view::RenderLayers,
+    ui::ContentSize,
+};
+use thiserror::Error;
+
+use crate::{
+    canvas::{
+        text::{SyltText, SyltTextAlign, SyltTextStyle},
+        ui_canvas::SyltUiText,
+    },
+    game::{
+        catalog::{GridDirection, NodeCatalog},
+        currency::SparkAmount,
+        grid::GridBounds,
+        save::GameStart,
+        simulation::{BoardRules, NodeTriggered, SparkSimulation},
+        system_set::{
+            SyltGamePauseState, SyltGameSystemSet, SyltPausableSystems,
+        },
+    },
+    i18n::SyltI18nText,
+    menus::SyltMenuState,
+    routes::SyltRouterState,
+    settings::SyltSettings,
+    signals::SyltSignal,
+    ui::constants::SU4,
+};
+
+/// Scenario files in the order they are listed in the menu
+const SCENARIO_FILES: [&str; 3] = [
+    "scenarios/first_steps.scenario.ron",
+    "scenarios/chain_reaction.scenario.ron",
+    "scenarios/tight_quarters.scenario.ron",
+];
+const RECORDS_FILE: &str = "scenarios.yaml";
+
+pub struct ScenarioPlugin;
+
+impl Plugin for ScenarioPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<ScenarioDefinition>()
+            .init_asset_loader::<ScenarioAssetLoader>()
+            .init_resource::<Scenarios>()
+            .init_resource::<ScenarioRecords>()
+            .add_systems(Startup, (load_scenarios, trigger_load_records))
+            .add_systems(
+                Update,
+                (set_scenarios_on_load, handle_records_loaded),
+            );
+
+        app.add_systems(OnEnter(SyltRouterState::Game), start_scenario);
+
+        app.add_systems(OnExit(SyltRouterState::Game), remove_active_scenario);
+
+        app.add_systems(
+            Update,
+            (track_scenario, update_scenario_hud)
+                .chain()
+                .in_set(SyltPausableSystems)
+                .in_set(SyltGameSystemSet::Update)
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<ActiveScenario>)
+                        .and(resource_exists::<SparkSimulation>),
+                ),
+        );
+    }
+}
+
+/// What has to be reached before the time runs out
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum ScenarioObjective {
+    /// Earn this many sparks, spending does not count against it
+    Sparks(SparkAmount),
+    /// Trigger a chain this many nodes deep
+    Chain(u32),
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ScenarioNode {
+    pub kind: String,
+    pub x: i32,
+    pub y: i32,
+    #[serde(default)]
+    pub facing: GridDirection,
+}
+
+/// A puzzle on a fixed board, as described in `assets/scenarios`
+#[derive(
+    Debug, Clone, Asset, TypePath, serde::Deserialize, serde::Serialize,
+)]
+pub struct ScenarioDefinition {
+    pub id: String,
+    pub i18n_key: String,
+    pub description_key: String,
+    /// Size of the grid, it can not be expanded
+    pub width: i32,
+    pub height: i32,
+    /// Cells nothing can be placed on
+    #[serde(default)]
+    pub blocked: Vec<(i32, i32)>,
+    pub starting_sparks: SparkAmount,
+    /// Nodes already on the board
+    #[serde(default)]
+    pub nodes: Vec<ScenarioNode>,
+    /// Node types that can be built
+    pub allowed: Vec<String>,
+    pub objective: ScenarioObjective,
+    /// Seconds to reach the objective in
+    pub time_limit: f32,
+}
+
+impl ScenarioDefinition {
+    /// Replaces the board with the one of the scenario. Upgrades start over
+    /// and prestige bonuses do not apply, every player gets the same puzzle.
+    pub fn start(&self, simulation: &mut SparkSimulation) {
+        simulation.start_new();
+        simulation.set_permanent_effects(Vec::new());
+        simulation.nodes.clear();
+
+        simulation.bounds = GridBounds {
+            min_x: 0,
+            min_y: 0,
+            max_x: self.width - 1,
+            max_y: self.height - 1,
+        };
+        simulation.rules = BoardRules {
+            allowed: Some(self.allowed.clone()),
+            blocked: self
+                .blocked
+                .iter()
+                .map(|(x, y)| IVec2::new(*x, *y))
+                .collect(),
+            fixed_bounds: true,
+        };
+        simulation.sparks.0 = self.starting_sparks;
+
+        for node in &self.nodes {
+            let position = IVec2::new(node.x, node.y);
+            simulation.insert_node(&node.kind, position);
+
+            if let Some(placed) = simulation.nodes.get_mut(&position) {
+                placed.facing = node.facing;
+            }
+        }
+    }
+
+    /// Checks that the nodes already on the board sit on free cells within
+    /// the grid
+    pub fn validate(&self) -> Result<(), ScenarioAssetLoaderError> {
+        for node in &self.nodes {
+            let position = IVec2::new(node.x, node.y);
+
+            if !(0..self.width).contains(&node.x)
+                || !(0..self.height).contains(&node.y)
+            {
+                return Err(ScenarioAssetLoaderError::NodeOutOfBounds(
+                    position,
+                ));
+            }
+
+            if self.blocked.contains(&(node.x, node.y)) {
+                return Err(ScenarioAssetLoaderError::NodeOnBlockedCell(
+                    position,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the allowed and placed node types are in the catalog,
+    /// the catalog is loaded separately from the scenarios
+    pub fn validate_kinds(
+        &self,
+        catalog: &NodeCatalog,
+    ) -> Result<(), ScenarioAssetLoaderError> {
+        let kinds = self
+            .allowed
+            .iter()
+            .chain(self.nodes.iter().map(|node| &node.kind));
+
+        for kind in kinds {
+            if catalog.get(kind).is_none() {
+                return Err(ScenarioAssetLoaderError::UnknownNode(
+                    kind.clone(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Every loaded scenario, in menu order
+#[derive(Resource, Default)]
+pub struct Scenarios(pub Vec<ScenarioDefinition>);
+
+impl Scenarios {
+    pub fn get(&self, id: &str) -> Option<&ScenarioDefinition> {
+        self.0.iter().find(|scenario| scenario.id == id)
+    }
+}
+
+/// Fastest wins in seconds, by scenario id
+#[derive(Resource, Default, serde::Deserialize, serde::Serialize)]
+pub struct ScenarioRecords(pub BTreeMap<String, f32>);
+
+impl ScenarioRecords {
+    pub fn best(&self, id: &str) -> Option<f32> {
+        self.0.get(id).copied()
+    }
+
+    /// Keeps the time if it beats the record, returns whether it did
+    pub fn record(&mut self, id: &str, seconds: f32) -> bool {
+        if self.best(id).is_some_and(|best| best <= seconds) {
+            return false;
+        }
+
+        self.0.insert(id.to_string(), seconds);
+        true
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScenarioOutcome {
+    Won,
+    Lost,
+}
+
+/// The scenario being played
+#[derive(Resource, Debug)]
+pub struct ActiveScenario {
+    pub id: String,
+    /// Seconds played, pauses excluded
+    pub elapsed: f32,
+    /// Deepest chain triggered so far
+    pub longest_chain: u32,
+    pub outcome: Option<ScenarioOutcome>,
+}
+
+impl ActiveScenario {
+    pub fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            elapsed: 0.,
+            longest_chain: 0,
+            outcome: None,
+        }
+    }
+
+    /// Advances the clock by `delta` seconds with the chains triggered in
+    /// that time. Returns the outcome once the objective is reached or the
+    /// time is up, and nothing after the scenario was decided.
+    pub fn advance(
+        &mut self,
+        scenario: &ScenarioDefinition,
+        lifetime_sparks: SparkAmount,
+        delta: f32,
+        chains: impl IntoIterator<Item = u32>,
+    ) -> Option<ScenarioOutcome> {
+        if self.outcome.is_some() {
+            return None;
+        }
+
+        self.elapsed += delta;
+
+        for depth in chains {
+            self.longest_chain = self.longest_chain.max(depth);
+        }
+
+        let won = match &scenario.objective {
+            ScenarioObjective::Sparks(amount) => lifetime_sparks >= *amount,
+            ScenarioObjective::Chain(depth) => self.longest_chain >= *depth,
+        };
+
+        let outcome = if won {
+            ScenarioOutcome::Won
+        } else if self.elapsed >= scenario.time_limit {
+            ScenarioOutcome::Lost
+        } else {
+            return None;
+        };
+
+        self.outcome = Some(outcome);
+        Some(outcome)
+    }
+}
+
+#[derive(Resource)]
+struct ScenarioAssets {
+    handles: Vec<Handle<ScenarioDefinition>>,
+}
+
+fn load_scenarios(mut cmd: Commands, asset_server: Res<AssetServer>) {
+    cmd.insert_resource(ScenarioAssets {
+        handles: SCENARIO_FILES
+            .iter()
+            .map(|path| asset_server.load(*path))
+            .collect(),
+    });
+}
+
+/// Scenarios with node types missing from the catalog are left out
+fn set_scenarios_on_load(
+    mut asset_events: EventReader<AssetEvent<ScenarioDefinition>>,
+    scenario_assets: Res<ScenarioAssets>,
+    scenario_files: Res<Assets<ScenarioDefinition>>,
+    catalog: Res<NodeCatalog>,
+    mut scenarios: ResMut<Scenarios>,
+) {
+    let changed = asset_events.read().any(|event| {
+        matches!(
+            event,
+            AssetEvent::LoadedWithDependencies { .. }
+                | AssetEvent::Modified { .. }
+        )
+    });
+
+    // Checked again once the catalog is loaded
+    if !(changed || catalog.is_changed()) || catalog.nodes.is_empty() {
+        return;
+    }
+
+    scenarios.0 = scenario_assets
+        .handles
+        .iter()
+        .filter_map(|handle| scenario_files.get(handle))
+        .filter(|scenario| match scenario.validate_kinds(&catalog) {
+            Ok(()) => true,
+            Err(e) => {
+                warn!("Invalid scenario {}: {e}", scenario.id);
+                false
+            }
+        })
+        .cloned()
+        .collect();
+}
+
+#[derive(Component)]
+struct ScenarioObjectiveText;
+
+#[derive(Component)]
+struct ScenarioTimeText;
+
+fn start_scenario(
+    mut cmd: Commands,
+    game_start: Res<GameStart>,
+    scenarios: Res<Scenarios>,
+) {
+    let GameStart::Scenario(id) = &*game_start else {
+        return;
+    };
+
+    let Some(scenario) = scenarios.get(id) else {
+        return;
+    };
+
+    cmd.insert_resource(ActiveScenario::new(id));
+
+    let objective_key = match scenario.objective {
+        ScenarioObjective::Sparks(_) => "scenario objective sparks",
+        ScenarioObjective::Chain(_) => "scenario objective chain",
+    };
+
+    cmd.spawn((
+        StateScoped(SyltRouterState::Game),
+        Node {
+            position_type: PositionType::Absolute,
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::FlexEnd,
+            top: Val::Px(SU4),
+            right: Val::Px(SU4),
+            row_gap: Val::Px(SU4),
+            ..default()
+        },
+        Pickable::IGNORE,
+    ))
+    .with_children(|parent| {
+        parent.spawn((hud_text(), SyltI18nText::from_key(&scenario.i18n_key)));
+        parent.spawn((hud_text(), SyltI18nText::from_key(objective_key)));
+        parent.spawn((hud_text(), ScenarioObjectiveText));
+        parent.spawn((hud_text(), ScenarioTimeText));
+    });
+}
+
+fn hud_text() -> impl Bundle {
+    (
+        RenderLayers::layer(1),
+        SyltUiText,
+        SyltTextStyle {
+            font_size: 28.,
+            ..default()
+        },
+        SyltTextAlign::Right,
+        SyltText::default(),
+        Node::default(),
+        ContentSize::default(),
+    )
+}
+
+fn remove_active_scenario(mut cmd: Commands) {
+    cmd.remove_resource::<ActiveScenario>();
+}
+
+/// Decides the scenario once the objective is reached or the time is up,
+/// then pauses the game behind the result screen
+#[allow(clippy::too_many_arguments)]
+fn track_scenario(
+    time: Res<Time>,
+    scenarios: Res<Scenarios>,
+    simulation: Res<SparkSimulation>,
+    mut node_triggered: EventReader<NodeTriggered>,
+    mut active: ResMut<ActiveScenario>,
+    mut records: ResMut<ScenarioRecords>,
+    mut sylt_signal_writer: EventWriter<SyltSignal>,
+    mut menu_state: ResMut<NextState<SyltMenuState>>,
+    mut pause_state: ResMut<NextState<SyltGamePauseState>>,
+) {
+    let Some(scenario) = scenarios.get(&active.id) else {
+        return;
+    };
+
+    let Some(outcome) = active.advance(
+        scenario,
+        simulation.lifetime_sparks,
+        time.delta_secs(),
+        node_triggered.read().map(|triggered| triggered.depth),
+    ) else {
+        return;
+    };
+
+    if outcome == ScenarioOutcome::Won {
+        let (id, elapsed) = (active.id.clone(), active.elapsed);
+
+        if records.record(&id, elapsed) {
+            write_records(&records, &mut sylt_signal_writer);
+        }
+    }
+
+    pause_state.set(SyltGamePauseState(true));
+    menu_state.set(SyltMenuState::ScenarioResult);
+}
+
+fn update_scenario_hud(
+    scenarios: Res<Scenarios>,
+    simulation: Res<SparkSimulation>,
+    active: Res<ActiveScenario>,
+    settings: Res<SyltSettings>,
+    mut objective_text: Single<
+        &mut SyltText,
+        (With<ScenarioObjectiveText>, Without<ScenarioTimeText>),
+    >,
+    mut time_text: Single<&mut SyltText, With<ScenarioTimeText>>,
+) {
+    let Some(scenario) = scenarios.get(&active.id) else {
+        return;
+    };
+
+    let notation = settings.number_notation.0;
+
+    objective_text.content = match &scenario.objective {
+        ScenarioObjective::Sparks(amount) => format!(
+            "{} / {} SP",
+            simulation.lifetime_sparks.format(notation),
+            amount.format(notation),
+        ),
+        ScenarioObjective::Chain(depth) => {
+            format!("{} / {depth}", active.longest_chain)
+        }
+    };
+
+    let remaining = (scenario.time_limit - active.elapsed).max(0.);
+    time_text.content = format!("{remaining:.0}s");
+}
+
+fn write_records(
+    records: &ScenarioRecords,
+    sylt_signal_writer: &mut EventWriter<SyltSignal>,
+) {
+    match serde_yaml::to_string(records) {
+        Ok(data) => {
+            sylt_signal_writer.write(SyltSignal::SaveFile {
+                key: RECORDS_FILE.into(),
+                data: data.into(),
+            });
+        }
+        Err(e) => warn!("Could not serialize the scenario records: {e}"),
+    }
+}
+
+fn trigger_load_records(mut sylt_signal_writer: EventWriter<SyltSignal>) {
+    sylt_signal_writer.write(SyltSignal::LoadFile {
+        key: RECORDS_FILE.into(),
+    });
+}
+
+fn handle_records_loaded(
+    mut sylt_signal_reader: EventReader<SyltSignal>,
+    mut records: ResMut<ScenarioRecords>,
+) {
+    for event in sylt_signal_reader.read() {
+        if let SyltSignal::FileLoaded { key, data } = event {
+            if key.to_string() != RECORDS_FILE {
+                continue;
+            }
+
+            match serde_yaml::from_str::<ScenarioRecords>(data) {
+                Ok(loaded) => *records = loaded,
+                Err(e) => warn!("Could not parse the scenario records: {e}"),
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ScenarioAssetLoader;
+
+impl AssetLoader for ScenarioAssetLoader {
+    type Asset = ScenarioDefinition;
+    type Settings = ();
+    type Error = ScenarioAssetLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let asset = ron::de::from_bytes::<Self::Asset>(&bytes)?;
+        asset.validate()?;
+        Ok(asset)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["scenario.ron"]
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum ScenarioAssetLoaderError {
+    #[error("Could not read the file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse RON: {0}")]
+    RonError(#[from] ron::error::SpannedError),
+    #[error("The node at {0} is outside of the grid")]
+    NodeOutOfBounds(IVec2),
+    #[error("The node at {0} is on a blocked cell")]
+    NodeOnBlockedCell(IVec2),
+    #[error("Unknown node type: {0}")]
+    UnknownNode(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{
+        catalog::NodeCatalogFile,
+        simulation::{SimulationAction, INITIAL_NODE},
+        upgrades::{UpgradeEffect, UpgradeTarget},
+    };
+
+    fn catalog() -> NodeCatalog {
+        let file = ron::de::from_str::<NodeCatalogFile>(
+            &std::fs::read_to_string("assets/nodes.ron").unwrap(),
+        )
+        .unwrap();
+
+        NodeCatalog {
+            nodes: file.nodes,
+            upgrades: file.upgrades,
+            prestige_bonuses: file.prestige_bonuses,
+        }
+    }
+
+    fn load(path: &str) -> ScenarioDefinition {
+        ron::de::from_str(
+            &std::fs::read_to_string(format!("assets/{path}")).unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn first_steps() -> (ScenarioDefinition, SparkSimulation) {
+        let catalog = catalog();
+        let scenario = load(SCENARIO_FILES[0]);
+        let mut simulation = SparkSimulation::new(
+            catalog.nodes.clone(),
+            catalog.upgrades.clone(),
+        );
+        scenario.start(&mut simulation);
+
+        (scenario, simulation)
+    }
+
+    #[test]
+    fn shipped_scenarios_are_valid() {
+        let catalog = catalog();
+
+        for path in SCENARIO_FILES {
+            let scenario = load(path);
+            assert!(scenario.validate().is_ok(), "{path}");
+            assert!(scenario.validate_kinds(&catalog).is_ok(), "{path}");
+        }
+    }
+
+    #[test]
+    fn invalid_nodes_are_rejected() {
+        let mut scenario = load(SCENARIO_FILES[1]);
+        scenario.nodes[0].x = scenario.width;
+        assert!(matches!(
+            scenario.validate(),
+            Err(ScenarioAssetLoaderError::NodeOutOfBounds(_))
+        ));
+
+        let (x, y) = scenario.blocked[0];
+        scenario.nodes[0].x = x;
+        scenario.nodes[0].y = y;
+        assert!(matches!(
+            scenario.validate(),
+            Err(ScenarioAssetLoaderError::NodeOnBlockedCell(position))
+                if position == IVec2::new(x, y)
+        ));
+    }
+
+    #[test]
+    fn unknown_node_types_are_rejected() {
+        let catalog = catalog();
+
+        let mut scenario = load(SCENARIO_FILES[0]);
+        scenario.allowed.push("teleporter".to_string());
+        assert!(matches!(
+            scenario.validate_kinds(&catalog),
+            Err(ScenarioAssetLoaderError::UnknownNode(kind))
+                if kind == "teleporter"
+        ));
+
+        let mut scenario = load(SCENARIO_FILES[0]);
+        scenario.nodes[0].kind = "core".to_string();
+        assert!(matches!(
+            scenario.validate_kinds(&catalog),
+            Err(ScenarioAssetLoaderError::UnknownNode(kind)) if kind == "core"
+        ));
+    }
+
+    #[test]
+    fn starting_replaces_the_board() {
+        let catalog = catalog();
+        let scenario = load(SCENARIO_FILES[1]);
+        let mut simulation = SparkSimulation::new(
+            catalog.nodes.clone(),
+            catalog.upgrades.clone(),
+        );
+        simulation.start_new();
+        simulation.insert_node("relay", IVec2::new(10, 10));
+        simulation.set_purchased(vec!["firm_clicks".to_string()]);
+
+        scenario.start(&mut simulation);
+
+        assert_eq!(simulation.bounds.width(), scenario.width);
+        assert_eq!(simulation.bounds.height(), scenario.height);
+        assert!(simulation.rules.fixed_bounds);
+        assert!(simulation.is_blocked(IVec2::new(3, 0)));
+        assert!(!simulation.rules.allows(INITIAL_NODE));
+        assert!(simulation.purchased().is_empty());
+        assert_eq!(simulation.sparks.0, scenario.starting_sparks);
+        assert_eq!(simulation.nodes.len(), 1);
+        assert_eq!(simulation.nodes[&IVec2::new(7, 1)].kind, INITIAL_NODE);
+    }
+
+    #[test]
+    fn prestige_bonuses_do_not_apply() {
+        let catalog = catalog();
+        let scenario = load(SCENARIO_FILES[0]);
+        let mut simulation = SparkSimulation::new(
+            catalog.nodes.clone(),
+            catalog.upgrades.clone(),
+        );
+        simulation.set_permanent_effects(vec![
+            UpgradeEffect::ClickMultiplier {
+                target: UpgradeTarget::All,
+                factor: 10.,
+            },
+        ]);
+
+        scenario.start(&mut simulation);
+        let outcome = simulation
+            .apply(SimulationAction::Click {
+                position: IVec2::new(2, 2),
+            })
+            .unwrap();
+
+        assert_eq!(outcome.earned[0].amount, SparkAmount::from(1.));
+    }
+
+    #[test]
+    fn reaching_the_objective_wins() {
+        let (scenario, mut simulation) = first_steps();
+        let mut active = ActiveScenario::new(&scenario.id);
+
+        for _ in 0..299 {
+            simulation
+                .apply(SimulationAction::Click {
+                    position: IVec2::new(2, 2),
+                })
+                .unwrap();
+        }
+        assert_eq!(
+            active.advance(&scenario, simulation.lifetime_sparks, 1., []),
+            None
+        );
+
+        simulation
+            .apply(SimulationAction::Click {
+                position: IVec2::new(2, 2),
+            })
+            .unwrap();
+        assert_eq!(
+            active.advance(&scenario, simulation.lifetime_sparks, 1., []),
+            Some(ScenarioOutcome::Won)
+        );
+        assert_eq!(active.elapsed, 2.);
+
+        // Decided once
+        assert_eq!(
+            active.advance(&scenario, simulation.lifetime_sparks, 1., []),
+            None
+        );
+        assert_eq!(active.elapsed, 2.);
+    }
+
+    #[test]
+    fn running_out_of_time_loses() {
+        let (scenario, mut simulation) = first_steps();
+        let mut active = ActiveScenario::new(&scenario.id);
+
+        // A stimulator makes the clicker yield a spark every second, not
+        // enough to reach the objective in time
+        simulation
+            .apply(SimulationAction::Build {
+                kind: "stimulator".to_string(),
+                position: IVec2::new(1, 2),
+            })
+            .unwrap();
+
+        let mut outcome = None;
+        while outcome.is_none() {
+            let step = simulation.step(0.25);
+            outcome = active.advance(
+                &scenario,
+                simulation.lifetime_sparks,
+                0.25,
+                step.triggered.iter().map(|triggered| triggered.depth),
+            );
+        }
+
+        assert_eq!(outcome, Some(ScenarioOutcome::Lost));
+        assert_eq!(active.elapsed, scenario.time_limit);
+        assert_eq!(active.longest_chain, 1);
+        assert!(simulation.lifetime_sparks > SparkAmount::from(100.));
+        assert!(simulation.lifetime_sparks < SparkAmount::from(300.));
+    }
+
+    #[test]
+    fn chain_objectives() {
+        let scenario = load(SCENARIO_FILES[1]);
+        let mut active = ActiveScenario::new(&scenario.id);
+
+        assert_eq!(
+            active.advance(&scenario, SparkAmount::ZERO, 1., [1, 3, 2]),
+            None
+        );
+        assert_eq!(active.longest_chain, 3);
+        assert_eq!(
+            active.advance(&scenario, SparkAmount::ZERO, 1., [4]),
+            Some(ScenarioOutcome::Won)
+        );
+    }
+}
@@ -0,0 +1,1896 @@
+use bevy::{
+    ecs::system::SystemParam,
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+use thiserror::Error;
+
+use crate::{
+    game::{
+        catalog::{GridDirection, NodeCatalog, NodeDefinition},
+        currency::SparkAmount,
+        grid::{GridBounds, GridExpansion},
+        offline::{offline_seconds, OfflineProgress},
+        prestige::PrestigeProgress,
+        pulses::{Pulse, PulseScheduler, NODE_COOLDOWN},
+        save::{GameStart, SavedGame},
+        scenario::Scenarios,
+        system_set::{SyltGameSystemSet, SyltPausableSystems},
+        upgrades::{Modifiers, UpgradeDefinition, UpgradeEffect, YieldSource},
+        Sparks,
+    },
+    routes::SyltRouterState,
+    settings::SyltSettings,
+};
+
+/// The node every game starts with in the middle of the grid
+pub const INITIAL_NODE: &str = "clicker";
+pub const INITIAL_POSITION: IVec2 = IVec2::new(4, 4);
+/// Step size in seconds when fast forwarding, matches the node cooldown so
+/// chains keep their rhythm while staying cheap to compute
+pub const FAST_FORWARD_STEP: f32 = NODE_COOLDOWN;
+/// Most steps a fast forward takes, ten minutes of game time. Anything
+/// longer is extrapolated so loading a save does not stall.
+pub const FAST_FORWARD_MAX_STEPS: u32 = 1200;
+/// Simulation steps per second of game time
+pub const SIMULATION_HZ: f64 = 30.;
+
+pub struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SimulationAction>();
+        app.add_event::<BuildRequested>();
+        app.add_event::<SimulationEvent>();
+        app.add_event::<SparksEarned>();
+        app.add_event::<SparksSpent>();
+        app.add_event::<NodeTriggered>();
+
+        app.add_systems(OnEnter(SyltRouterState::Game), init_simulation);
+
+        app.add_systems(OnExit(SyltRouterState::Game), remove_simulation);
+
+        app.insert_resource(Time::<Fixed>::from_hz(SIMULATION_HZ));
+
+        // Requests from the input systems are settled before anything reacts
+        // to their outcome
+        app.add_systems(
+            Update,
+            apply_actions
+                .in_set(SyltPausableSystems)
+                .after(SyltGameSystemSet::Input)
+                .before(SyltGameSystemSet::Update)
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<SparkSimulation>),
+                ),
+        );
+
+        app.add_systems(
+            Update,
+            sync_catalog
+                .in_set(SyltPausableSystems)
+                .in_set(SyltGameSystemSet::Update)
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<SparkSimulation>),
+                ),
+        );
+
+        // Fixed steps follow the virtual clock, so a faster game takes more
+        // steps of the same size and plays out the same way
+        app.add_systems(
+            FixedUpdate,
+            step_simulation.in_set(SyltPausableSystems).run_if(
+                in_state(SyltRouterState::Game)
+                    .and(resource_exists::<SparkSimulation>),
+            ),
+        );
+    }
+}
+
+/// Player input the simulation understands. Input systems send these as
+/// events, [`SparkSimulation::apply`] decides whether they go through and
+/// the outcome is sent on as [`SimulationEvent`]s.
+#[derive(Event, Debug, Clone, PartialEq)]
+pub enum SimulationAction {
+    /// Manually trigger the node at a position
+    Click {
+        position: IVec2,
+    },
+    /// Buy and place a node on an empty cell
+    Build {
+        kind: String,
+        position: IVec2,
+    },
+    /// Buy more cells for the board
+    ExpandGrid {
+        expansion: GridExpansion,
+    },
+    /// Remove a node for a part of its price
+    Sell {
+        position: IVec2,
+    },
+    /// Move a node to an empty cell
+    Move {
+        from: IVec2,
+        to: IVec2,
+    },
+    /// Exchange the places of two nodes
+    Swap {
+        first: IVec2,
+        second: IVec2,
+    },
+    BuyUpgrade {
+        id: String,
+    },
+    /// Turn a directional node an eighth of a turn
+    Rotate {
+        position: IVec2,
+        clockwise: bool,
+    },
+    /// Take an action back, restoring the board and the balance
+    Undo(ReversibleAction),
+    /// Take an undone action again
+    Redo(ReversibleAction),
+}
+
+/// An action the player took on the board, with what it takes to take it
+/// back
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReversibleAction {
+    Build {
+        kind: String,
+        position: IVec2,
+        cost: SparkAmount,
+    },
+    Sell {
+        kind: String,
+        position: IVec2,
+        facing: GridDirection,
+        refund: SparkAmount,
+    },
+    Move {
+        from: IVec2,
+        to: IVec2,
+    },
+    Swap {
+        first: IVec2,
+        second: IVec2,
+    },
+    BuyUpgrade {
+        id: String,
+        cost: SparkAmount,
+    },
+}
+
+impl ReversibleAction {
+    pub fn from_event(event: &SimulationEvent) -> Option<Self> {
+        match event {
+            SimulationEvent::Built {
+                kind,
+                position,
+                cost,
+            } => Some(Self::Build {
+                kind: kind.clone(),
+                position: *position,
+                cost: *cost,
+            }),
+            SimulationEvent::Sold {
+                kind,
+                position,
+                facing,
+                refund,
+            } => Some(Self::Sell {
+                kind: kind.clone(),
+                position: *position,
+                facing: *facing,
+                refund: *refund,
+            }),
+            SimulationEvent::Moved { from, to } => Some(Self::Move {
+                from: *from,
+                to: *to,
+            }),
+            SimulationEvent::Swapped { first, second } => Some(Self::Swap {
+                first: *first,
+                second: *second,
+            }),
+            SimulationEvent::UpgradeBought { id, cost } => {
+                Some(Self::BuyUpgrade {
+                    id: id.clone(),
+                    cost: *cost,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// The same action again, the restored board makes it cost the same
+    fn action(&self) -> SimulationAction {
+        match self {
+            Self::Build { kind, position, .. } => SimulationAction::Build {
+                kind: kind.clone(),
+                position: *position,
+            },
+            Self::Sell { position, .. } => SimulationAction::Sell {
+                position: *position,
+            },
+            Self::Move { from, to } => SimulationAction::Move {
+                from: *from,
+                to: *to,
+            },
+            Self::Swap { first, second } => SimulationAction::Swap {
+                first: *first,
+                second: *second,
+            },
+            Self::BuyUpgrade { id, .. } => {
+                SimulationAction::BuyUpgrade { id: id.clone() }
+            }
+        }
+    }
+}
+
+/// The player wants a node placed facing a direction. Handled as a
+/// [`SimulationAction::Build`] followed by the turns to face the direction.
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct BuildRequested {
+    pub kind: String,
+    pub position: IVec2,
+    pub facing: GridDirection,
+}
+
+/// Changes to the board during a step or an action, used by the game
+/// systems to animate nodes and play sounds. Balance changes and triggers
+/// are sent as their own events.
+#[derive(Event, Debug, Clone, PartialEq)]
+pub enum SimulationEvent {
+    /// A node sent pulses to the nodes in its trigger pattern
+    Pulsed {
+        position: IVec2,
+        targets: Vec<IVec2>,
+        depth: u32,
+        /// Seconds until the pulses arrive
+        delay: f32,
+    },
+    /// A node was triggered by the player
+    Clicked {
+        position: IVec2,
+    },
+    Built {
+        kind: String,
+        position: IVec2,
+        cost: SparkAmount,
+    },
+    GridExpanded {
+        bounds: GridBounds,
+        cost: SparkAmount,
+    },
+    Sold {
+        kind: String,
+        position: IVec2,
+        facing: GridDirection,
+        refund: SparkAmount,
+    },
+    Moved {
+        from: IVec2,
+        to: IVec2,
+    },
+    Swapped {
+        first: IVec2,
+        second: IVec2,
+    },
+    UpgradeBought {
+        id: String,
+        cost: SparkAmount,
+    },
+    Rotated {
+        position: IVec2,
+        facing: GridDirection,
+    },
+    /// An action was taken back, the board changes it caused are reverted
+    /// without events of their own
+    Undone(ReversibleAction),
+    /// An undone action was taken again
+    Redone(ReversibleAction),
+}
+
+/// Where sparks added to the balance came from
+#[derive(Debug, Clone, PartialEq)]
+pub enum EarnedFrom {
+    /// The player clicked the node
+    Click { position: IVec2, kind: String },
+    /// A pulse reached the node, sent by a node of type `source_kind`
+    Trigger {
+        position: IVec2,
+        source_kind: String,
+    },
+    /// A share of the price of a sold node
+    Sale,
+    /// The price of an undone purchase
+    Refund,
+}
+
+/// The balance went up
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct SparksEarned {
+    pub amount: SparkAmount,
+    pub from: EarnedFrom,
+}
+
+impl SparksEarned {
+    /// The node that generated the sparks, none for sparks that were not
+    /// generated
+    pub fn yielded_at(&self) -> Option<IVec2> {
+        match self.from {
+            EarnedFrom::Click { position, .. }
+            | EarnedFrom::Trigger { position, .. } => Some(position),
+            EarnedFrom::Sale | EarnedFrom::Refund => None,
+        }
+    }
+
+    /// The node type the sparks are credited to: the clicked node, or the
+    /// node whose pulse made the target yield
+    pub fn node_kind(&self) -> Option<&str> {
+        match &self.from {
+            EarnedFrom::Click { kind, .. } => Some(kind),
+            EarnedFrom::Trigger { source_kind, .. } => Some(source_kind),
+            EarnedFrom::Sale | EarnedFrom::Refund => None,
+        }
+    }
+}
+
+/// What sparks taken from the balance paid for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpentOn {
+    Build,
+    Grid,
+    Upgrade,
+    /// Paying the refund back when a sale is undone
+    Buyback,
+}
+
+/// The balance went down
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct SparksSpent {
+    pub amount: SparkAmount,
+    pub on: SpentOn,
+}
+
+/// A pulse reached a node
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct NodeTriggered {
+    pub source: IVec2,
+    pub target: IVec2,
+    /// Hops since the node that started the chain
+    pub depth: u32,
+}
+
+/// Everything a step or an action caused, in the order it happened
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimulationOutcome {
+    pub events: Vec<SimulationEvent>,
+    pub earned: Vec<SparksEarned>,
+    pub spent: Vec<SparksSpent>,
+    pub triggered: Vec<NodeTriggered>,
+}
+
+impl SimulationOutcome {
+    pub fn extend(&mut self, other: SimulationOutcome) {
+        self.events.extend(other.events);
+        self.earned.extend(other.earned);
+        self.spent.extend(other.spent);
+        self.triggered.extend(other.triggered);
+    }
+
+    fn event(event: SimulationEvent) -> Self {
+        Self {
+            events: vec![event],
+            ..default()
+        }
+    }
+
+    fn with_spent(mut self, amount: SparkAmount, on: SpentOn) -> Self {
+        self.spent.push(SparksSpent { amount, on });
+        self
+    }
+
+    fn with_earned(mut self, amount: SparkAmount, from: EarnedFrom) -> Self {
+        self.earned.push(SparksEarned { amount, from });
+        self
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug, Error, PartialEq)]
+pub enum SimulationError {
+    #[error("Unknown node kind: {0}")]
+    UnknownNode(String),
+    #[error("Node kind can not be built: {0}")]
+    NotBuildable(String),
+    #[error("Cell {0} is already occupied")]
+    Occupied(IVec2),
+    #[error("Cell {0} is empty")]
+    Empty(IVec2),
+    #[error("Cell {0} is outside of the grid")]
+    OutOfBounds(IVec2),
+    #[error("Cell {0} is blocked")]
+    Blocked(IVec2),
+    #[error("The node at {0} is the last one producing sparks")]
+    LastProducer(IVec2),
+    #[error("The node at {0} has no direction")]
+    NotRotatable(IVec2),
+    #[error("Unknown upgrade: {0}")]
+    UnknownUpgrade(String),
+    #[error("Upgrade {0} was already bought")]
+    AlreadyPurchased(String),
+    #[error("Upgrade {0} needs {1} first")]
+    MissingPrerequisite(String, String),
+    #[error("The grid can not grow any further")]
+    GridAtMaximum,
+    #[error("The grid size is fixed")]
+    FixedGrid,
+    #[error("Not enough sparks, {cost} needed")]
+    InsufficientSparks { cost: SparkAmount },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationNode {
+    /// Id of the [`NodeDefinition`] this node is built from
+    pub kind: String,
+    /// Seconds since the node last pulsed on its own
+    pub pulse_elapsed: f32,
+    /// Where directional nodes send their pulses
+    pub facing: GridDirection,
+}
+
+/// Limits a scenario puts on the board, endless games have none
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BoardRules {
+    /// Node types that can be built, every buildable type when `None`
+    pub allowed: Option<Vec<String>>,
+    /// Cells within the bounds nothing can be placed on
+    pub blocked: HashSet<IVec2>,
+    pub fixed_bounds: bool,
+}
+
+impl BoardRules {
+    pub fn allows(&self, kind: &str) -> bool {
+        self.allowed
+            .as_ref()
+            .is_none_or(|allowed| allowed.iter().any(|id| id == kind))
+    }
+}
+
+/// Headless spark economy, owns the grid occupancy, node timers and the
+/// spark balance. Advanced with [`SparkSimulation::step`] and driven by the
+/// player through [`SparkSimulation::apply`].
+#[derive(Resource)]
+pub struct SparkSimulation {
+    definitions: Vec<NodeDefinition>,
+    upgrades: Vec<UpgradeDefinition>,
+    /// Ids of the bought upgrades, in order of purchase
+    purchased: Vec<String>,
+    /// Effects that outlast the game, from prestige bonuses
+    permanent_effects: Vec<UpgradeEffect>,
+    modifiers: Modifiers,
+    /// Placed nodes by cell, also the occupancy index trigger patterns are
+    /// checked against
+    pub nodes: HashMap<IVec2, SimulationNode>,
+    pub bounds: GridBounds,
+    pub rules: BoardRules,
+    pub sparks: Sparks,
+    /// Every spark earned this game, spending does not lower it
+    pub lifetime_sparks: SparkAmount,
+    pub scheduler: PulseScheduler,
+}
+
+impl SparkSimulation {
+    pub fn new(
+        definitions: Vec<NodeDefinition>,
+        upgrades: Vec<UpgradeDefinition>,
+    ) -> Self {
+        Self {
+            definitions,
+            upgrades,
+            purchased: Vec::new(),
+            permanent_effects: Vec::new(),
+            modifiers: Modifiers::default(),
+            nodes: HashMap::default(),
+            bounds: GridBounds::default(),
+            rules: BoardRules::default(),
+            sparks: Sparks(SparkAmount::ZERO),
+            lifetime_sparks: SparkAmount::ZERO,
+            scheduler: PulseScheduler::default(),
+        }
+    }
+
+    pub fn definition(&self, kind: &str) -> Option<&NodeDefinition> {
+        self.definitions
+            .iter()
+            .find(|definition| definition.id == kind)
+    }
+
+    /// Node types the player can build under the current rules
+    pub fn buildable(&self) -> impl Iterator<Item = &NodeDefinition> {
+        self.definitions.iter().filter(|definition| {
+            definition.buildable && self.rules.allows(&definition.id)
+        })
+    }
+
+    pub fn is_blocked(&self, position: IVec2) -> bool {
+        self.rules.blocked.contains(&position)
+    }
+
+    /// Cells within the bounds that are not blocked
+    pub fn open_cells(&self) -> usize {
+        self.bounds
+            .cells()
+            .filter(|position| !self.is_blocked(*position))
+            .count()
+    }
+
+    pub fn set_definitions(&mut self, definitions: Vec<NodeDefinition>) {
+        self.definitions = definitions;
+    }
+
+    pub fn set_upgrades(&mut self, upgrades: Vec<UpgradeDefinition>) {
+        self.upgrades = upgrades;
+        self.update_modifiers();
+    }
+
+    pub fn upgrade(&self, id: &str) -> Option<&UpgradeDefinition> {
+        self.upgrades.iter().find(|upgrade| upgrade.id == id)
+    }
+
+    pub fn purchased(&self) -> &[String] {
+        &self.purchased
+    }
+
+    /// Marks upgrades as bought without paying for them
+    pub fn set_purchased(&mut self, purchased: Vec<String>) {
+        self.purchased = purchased;
+        self.update_modifiers();
+    }
+
+    /// Upgrades not bought yet whose prerequisites are all bought
+    pub fn available_upgrades(
+        &self,
+    ) -> impl Iterator<Item = &UpgradeDefinition> {
+        self.upgrades.iter().filter(|upgrade| {
+            !self.purchased.contains(&upgrade.id)
+                && upgrade
+                    .requires
+                    .iter()
+                    .all(|required| self.purchased.contains(required))
+        })
+    }
+
+    pub fn set_permanent_effects(&mut self, effects: Vec<UpgradeEffect>) {
+        self.permanent_effects = effects;
+        self.update_modifiers();
+    }
+
+    fn update_modifiers(&mut self) {
+        let effects = self
+            .upgrades
+            .iter()
+            .filter(|upgrade| self.purchased.contains(&upgrade.id))
+            .flat_map(|upgrade| upgrade.effects.iter())
+            .chain(self.permanent_effects.iter())
+            .cloned()
+            .collect();
+
+        self.modifiers = Modifiers::new(effects);
+    }
+
+    /// Clears the board, balance and upgrades and places the initial node.
+    /// Definitions and permanent effects stay.
+    pub fn start_new(&mut self) {
+        self.nodes.clear();
+        self.bounds = GridBounds::default();
+        self.rules = BoardRules::default();
+        self.sparks = Sparks(SparkAmount::ZERO);
+        self.lifetime_sparks = SparkAmount::ZERO;
+        self.scheduler = PulseScheduler::default();
+        self.set_purchased(Vec::new());
+        self.insert_node(INITIAL_NODE, INITIAL_POSITION);
+    }
+
+    pub fn modifiers(&self) -> &Modifiers {
+        &self.modifiers
+    }
+
+    /// Number of placed nodes of a type
+    pub fn owned(&self, kind: &str) -> usize {
+        self.nodes.values().filter(|node| node.kind == kind).count()
+    }
+
+    /// What the next node of a type costs, the price grows with every node
+    /// of that type already placed
+    pub fn price(&self, kind: &str) -> Option<SparkAmount> {
+        self.definition(kind)
+            .map(|definition| definition.price(self.owned(kind)))
+    }
+
+    /// Sparks returned for selling the node at a position, a share of what
+    /// the most recent node of its type cost
+    pub fn refund(&self, position: IVec2) -> Option<SparkAmount> {
+        let node = self.nodes.get(&position)?;
+        let definition = self.definition(&node.kind)?;
+        let price = definition.price(self.owned(&node.kind).saturating_sub(1));
+
+        price.checked_mul(SparkAmount::from(
+            definition.refund_percentage.clamp(0., 100.) / 100.,
+        ))
+    }
+
+    /// Places a node without paying for it
+    pub fn insert_node(&mut self, kind: &str, position: IVec2) {
+        self.nodes.insert(
+            position,
+            SimulationNode {
+                kind: kind.to_string(),
+                pulse_elapsed: 0.,
+                facing: GridDirection::default(),
+            },
+        );
+    }
+
+    /// Node positions in a stable order, so steps are deterministic
+    fn sorted_positions(&self) -> Vec<IVec2> {
+        let mut positions: Vec<IVec2> = self.nodes.keys().copied().collect();
+        positions.sort_by_key(|position| (position.y, position.x));
+        positions
+    }
+
+    pub fn step(&mut self, delta: f32) -> SimulationOutcome {
+        let mut outcome = SimulationOutcome::default();
+
+        // The pulse clock moves first, so pulses sent by the node timers
+        // below take their full delay from the end of the step
+        for pulse in self.scheduler.advance(delta) {
+            self.trigger(pulse, &mut outcome);
+        }
+
+        for position in self.sorted_positions() {
+            let Some(node) = self.nodes.get(&position) else {
+                continue;
+            };
+
+            let Some(pulse_interval) =
+                self.definition(&node.kind).and_then(|definition| {
+                    self.modifiers.pulse_interval(definition)
+                })
+            else {
+                continue;
+            };
+
+            let mut pulses = 0;
+
+            if let Some(node) = self.nodes.get_mut(&position) {
+                node.pulse_elapsed += delta;
+
+                while node.pulse_elapsed >= pulse_interval {
+                    node.pulse_elapsed -= pulse_interval;
+                    pulses += 1;
+                }
+            }
+
+            for _ in 0..pulses {
+                self.emit(position, 1, &mut outcome);
+            }
+        }
+
+        outcome
+    }
+
+    /// Runs the simulation for a long duration in coarse steps, discarding
+    /// the events, and returns the sparks earned. At most
+    /// [`FAST_FORWARD_MAX_STEPS`] are simulated, the board can not change
+    /// while nobody plays so the rest earns at the same rate.
+    pub fn fast_forward(&mut self, seconds: f32) -> SparkAmount {
+        let sparks_before = self.sparks.0;
+        let simulated =
+            seconds.min(FAST_FORWARD_STEP * FAST_FORWARD_MAX_STEPS as f32);
+        let mut remaining = simulated;
+
+        while remaining > 0. {
+            let delta = remaining.min(FAST_FORWARD_STEP);
+            self.step(delta);
+            remaining -= delta;
+        }
+
+        let earned =
+            self.sparks.0.checked_sub(sparks_before).unwrap_or_default();
+
+        if seconds <= simulated {
+            return earned;
+        }
+
+        let extrapolated = earned
+            .checked_mul(SparkAmount::from(
+                (seconds - simulated) as f64 / simulated as f64,
+            ))
+            .unwrap_or(SparkAmount::MAX);
+        self.sparks.0 += extrapolated;
+        self.lifetime_sparks += extrapolated;
+
+        self.sparks.0.checked_sub(sparks_before).unwrap_or_default()
+    }
+
+    pub fn apply(
+        &mut self,
+        action: SimulationAction,
+    ) -> Result<SimulationOutcome, SimulationError> {
+        match action {
+            SimulationAction::Click { position } => {
+                let node = self
+                    .nodes
+                    .get(&position)
+                    .ok_or(SimulationError::Empty(position))?;
+
+                let definition =
+                    self.definition(&node.kind).ok_or_else(|| {
+                        SimulationError::UnknownNode(node.kind.clone())
+                    })?;
+
+                // Only nodes that yield sparks can be clicked
+                if definition.yields.is_zero() {
+                    return Ok(SimulationOutcome::default());
+                }
+
+                let amount =
+                    self.modifiers.yields(definition, YieldSource::Click);
+                let kind = definition.id.clone();
+                self.sparks.0 += amount;
+                self.lifetime_sparks += amount;
+
+                Ok(SimulationOutcome::event(SimulationEvent::Clicked {
+                    position,
+                })
+                .with_earned(amount, EarnedFrom::Click { position, kind }))
+            }
+            SimulationAction::Build { kind, position } => {
+                let definition = self.definition(&kind).ok_or_else(|| {
+                    SimulationError::UnknownNode(kind.clone())
+                })?;
+
+                if !definition.buildable || !self.rules.allows(&kind) {
+                    return Err(SimulationError::NotBuildable(kind));
+                }
+
+                let cost = definition.price(self.owned(&kind));
+
+                if !self.bounds.contains(position) {
+                    return Err(SimulationError::OutOfBounds(position));
+                }
+
+                if self.is_blocked(position) {
+                    return Err(SimulationError::Blocked(position));
+                }
+
+                if self.nodes.contains_key(&position) {
+                    return Err(SimulationError::Occupied(position));
+                }
+
+                let Some(remaining) = self.sparks.0.checked_sub(cost) else {
+                    return Err(SimulationError::InsufficientSparks { cost });
+                };
+
+                self.sparks.0 = remaining;
+                self.insert_node(&kind, position);
+
+                Ok(SimulationOutcome::event(SimulationEvent::Built {
+                    kind,
+                    position,
+                    cost,
+                })
+                .with_spent(cost, SpentOn::Build))
+            }
+            SimulationAction::ExpandGrid { expansion } => {
+                if self.rules.fixed_bounds {
+                    return Err(SimulationError::FixedGrid);
+                }
+
+                let bounds = self
+                    .bounds
+                    .expanded(expansion)
+                    .ok_or(SimulationError::GridAtMaximum)?;
+
+                let cost = self.bounds.expansion_cost(&bounds);
+
+                let Some(remaining) = self.sparks.0.checked_sub(cost) else {
+                    return Err(SimulationError::InsufficientSparks { cost });
+                };
+
+                self.sparks.0 = remaining;
+                self.bounds = bounds;
+
+                Ok(SimulationOutcome::event(SimulationEvent::GridExpanded {
+                    bounds,
+                    cost,
+                })
+                .with_spent(cost, SpentOn::Grid))
+            }
+            SimulationAction::Sell { position } => {
+                let refund = self
+                    .refund(position)
+                    .ok_or(SimulationError::Empty(position))?;
+
+                // Selling the only source of sparks could lock the game
+                let producers = self
+                    .nodes
+                    .values()
+                    .filter(|node| {
+                        self.definition(&node.kind).is_some_and(|definition| {
+                            !definition.yields.is_zero()
+                        })
+                    })
+                    .count();
+
+                let produces = self
+                    .nodes
+                    .get(&position)
+                    .and_then(|node| self.definition(&node.kind))
+                    .is_some_and(|definition| !definition.yields.is_zero());
+
+                if produces && producers == 1 {
+                    return Err(SimulationError::LastProducer(position));
+                }
+
+                let Some(node) = self.nodes.remove(&position) else {
+                    return Err(SimulationError::Empty(position));
+                };
+
+                self.sparks.0 += refund;
+
+                Ok(SimulationOutcome::event(SimulationEvent::Sold {
+                    kind: node.kind,
+                    position,
+                    facing: node.facing,
+                    refund,
+                })
+                .with_earned(refund, EarnedFrom::Sale))
+            }
+            SimulationAction::BuyUpgrade { id } => {
+                let upgrade = self.upgrade(&id).ok_or_else(|| {
+                    SimulationError::UnknownUpgrade(id.clone())
+                })?;
+
+                if self.purchased.contains(&id) {
+                    return Err(SimulationError::AlreadyPurchased(id));
+                }
+
+                if let Some(missing) = upgrade
+                    .requires
+                    .iter()
+                    .find(|required| !self.purchased.contains(required))
+                {
+                    return Err(SimulationError::MissingPrerequisite(
+                        id.clone(),
+                        missing.clone(),
+                    ));
+                }
+
+                let cost = upgrade.cost;
+
+                let Some(remaining) = self.sparks.0.checked_sub(cost) else {
+                    return Err(SimulationError::InsufficientSparks { cost });
+                };
+
+                self.sparks.0 = remaining;
+                self.purchased.push(id.clone());
+                self.update_modifiers();
+
+                Ok(SimulationOutcome::event(SimulationEvent::UpgradeBought {
+                    id,
+                    cost,
+                })
+                .with_spent(cost, SpentOn::Upgrade))
+            }
+            SimulationAction::Move { from, to } => {
+                if !self.bounds.contains(to) {
+                    return Err(SimulationError::OutOfBounds(to));
+                }
+
+                if self.is_blocked(to) {
+                    return Err(SimulationError::Blocked(to));
+                }
+
+                if self.nodes.contains_key(&to) {
+                    return Err(SimulationError::Occupied(to));
+                }
+
+                let node = self
+                    .nodes
+                    .remove(&from)
+                    .ok_or(SimulationError::Empty(from))?;
+
+                self.nodes.insert(to, node);
+
+                Ok(SimulationOutcome::event(SimulationEvent::Moved {
+                    from,
+                    to,
+                }))
+            }
+            SimulationAction::Swap { first, second } => {
+                if !self.nodes.contains_key(&first) {
+                    return Err(SimulationError::Empty(first));
+                }
+
+                let Some(second_node) = self.nodes.remove(&second) else {
+                    return Err(SimulationError::Empty(second));
+                };
+
+                if let Some(first_node) = self.nodes.remove(&first) {
+                    self.nodes.insert(second, first_node);
+                }
+
+                self.nodes.insert(first, second_node);
+
+                Ok(SimulationOutcome::event(SimulationEvent::Swapped {
+                    first,
+                    second,
+                }))
+            }
+            SimulationAction::Rotate {
+                position,
+                clockwise,
+            } => {
+                let node = self
+                    .nodes
+                    .get(&position)
+                    .ok_or(SimulationError::Empty(position))?;
+
+                let definition =
+                    self.definition(&node.kind).ok_or_else(|| {
+                        SimulationError::UnknownNode(node.kind.clone())
+                    })?;
+
+                if !definition.trigger_pattern.is_directional() {
+                    return Err(SimulationError::NotRotatable(position));
+                }
+
+                let facing = node.facing.rotated(clockwise);
+
+                if let Some(node) = self.nodes.get_mut(&position) {
+                    node.facing = facing;
+                }
+
+                Ok(SimulationOutcome::event(SimulationEvent::Rotated {
+                    position,
+                    facing,
+                }))
+            }
+            SimulationAction::Undo(action) => self.undo(action),
+            SimulationAction::Redo(action) => {
+                let mut outcome = self.apply(action.action())?;
+                outcome.events = vec![SimulationEvent::Redone(action)];
+
+                Ok(outcome)
+            }
+        }
+    }
+
+    /// Restores the board and the balance from before the action
+    fn undo(
+        &mut self,
+        action: ReversibleAction,
+    ) -> Result<SimulationOutcome, SimulationError> {
+        let mut outcome =
+            SimulationOutcome::event(SimulationEvent::Undone(action.clone()));
+
+        match action {
+            ReversibleAction::Build {
+                kind,
+                position,
+                cost,
+            } => {
+                if self
+                    .nodes
+                    .get(&position)
+                    .is_none_or(|node| node.kind != kind)
+                {
+                    return Err(SimulationError::Empty(position));
+                }
+
+                self.nodes.remove(&position);
+                self.sparks.0 += cost;
+                outcome = outcome.with_earned(cost, EarnedFrom::Refund);
+            }
+            ReversibleAction::Sell {
+                kind,
+                position,
+                facing,
+                refund,
+            } => {
+                if self.nodes.contains_key(&position) {
+                    return Err(SimulationError::Occupied(position));
+                }
+
+                let Some(remaining) = self.sparks.0.checked_sub(refund) else {
+                    return Err(SimulationError::InsufficientSparks {
+                        cost: refund,
+                    });
+                };
+
+                self.sparks.0 = remaining;
+                self.insert_node(&kind, position);
+
+                if let Some(node) = self.nodes.get_mut(&position) {
+                    node.facing = facing;
+                }
+
+                outcome = outcome.with_spent(refund, SpentOn::Buyback);
+            }
+            ReversibleAction::Move { from, to } => {
+                self.apply(SimulationAction::Move { from: to, to: from })?;
+            }
+            ReversibleAction::Swap { first, second } => {
+                self.apply(SimulationAction::Swap { first, second })?;
+            }
+            ReversibleAction::BuyUpgrade { id, cost } => {
+                let Some(index) =
+                    self.purchased.iter().position(|bought| *bought == id)
+                else {
+                    return Err(SimulationError::UnknownUpgrade(id));
+                };
+
+                self.purchased.remove(index);
+                self.update_modifiers();
+                self.sparks.0 += cost;
+                outcome = outcome.with_earned(cost, EarnedFrom::Refund);
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    /// Builds a node and turns it the shortest way to the requested facing
+    pub fn build_facing(
+        &mut self,
+        request: &BuildRequested,
+    ) -> Result<SimulationOutcome, SimulationError> {
+        let mut outcome = self.apply(SimulationAction::Build {
+            kind: request.kind.clone(),
+            position: request.position,
+        })?;
+
+        // New nodes face north, the first of the directions
+        let index = GridDirection::ALL
+            .iter()
+            .position(|direction| *direction == request.facing)
+            .unwrap_or_default();
+        let (turns, clockwise) = if index <= GridDirection::ALL.len() / 2 {
+            (index, true)
+        } else {
+            (GridDirection::ALL.len() - index, false)
+        };
+
+        // The node is built either way, nodes without a direction just
+        // keep facing north
+        for _ in 0..turns {
+            let Ok(rotated) = self.apply(SimulationAction::Rotate {
+                position: request.position,
+                clockwise,
+            }) else {
+                break;
+            };
+
+            outcome.extend(rotated);
+        }
+
+        Ok(outcome)
+    }
+
+    /// Sends pulses from a node to the occupied cells of its trigger pattern
+    fn emit(
+        &mut self,
+        source: IVec2,
+        depth: u32,
+        outcome: &mut SimulationOutcome,
+    ) {
+        let Some((definition, kind, facing)) =
+            self.nodes.get(&source).and_then(|node| {
+                self.definition(&node.kind).map(|definition| {
+                    (definition, node.kind.clone(), node.facing)
+                })
+            })
+        else {
+            return;
+        };
+
+        // The node map doubles as the occupancy index of the grid
+        let targets = definition.trigger_pattern.targets(
+            source,
+            facing,
+            self.modifiers.trigger_reach(definition),
+            |cell| self.nodes.contains_key(&cell),
+        );
+        let delay = definition.pulse_delay();
+
+        if targets.is_empty() || !self.scheduler.try_cooldown(source) {
+            return;
+        }
+
+        self.scheduler
+            .schedule(source, &kind, &targets, depth, delay);
+        outcome.events.push(SimulationEvent::Pulsed {
+            position: source,
+            targets,
+            depth,
+            delay,
+        });
+    }
+
+    fn trigger(&mut self, pulse: Pulse, outcome: &mut SimulationOutcome) {
+        let Some(definition) = self
+            .nodes
+            .get(&pulse.target)
+            .and_then(|node| self.definition(&node.kind))
+        else {
+            return;
+        };
+
+        let amount = self.modifiers.yields(definition, YieldSource::Trigger);
+
+        outcome.triggered.push(NodeTriggered {
+            source: pulse.source,
+            target: pulse.target,
+            depth: pulse.depth,
+        });
+
+        if !amount.is_zero() {
+            self.sparks.0 += amount;
+            self.lifetime_sparks += amount;
+            outcome.earned.push(SparksEarned {
+                amount,
+                from: EarnedFrom::Trigger {
+                    position: pulse.target,
+                    source_kind: pulse.source_kind.clone(),
+                },
+            });
+        }
+
+        // Triggered nodes pass the pulse on to their own neighbours
+        self.emit(pulse.target, pulse.depth + 1, outcome);
+    }
+}
+
+fn init_simulation(
+    mut cmd: Commands,
+    catalog: Res<NodeCatalog>,
+    game_start: Res<GameStart>,
+    saved_game: Res<SavedGame>,
+    settings: Res<SyltSettings>,
+    prestige_progress: Res<PrestigeProgress>,
+    scenarios: Res<Scenarios>,
+) {
+    let mut simulation =
+        SparkSimulation::new(catalog.nodes.clone(), catalog.upgrades.clone());
+    simulation.set_permanent_effects(prestige_progress.effects(&catalog));
+
+    // Scenarios clear the permanent effects again when they start
+
+    if let GameStart::Scenario(id) = &*game_start {
+        match scenarios.get(id) {
+            Some(scenario) => scenario.start(&mut simulation),
+            None => {
+                warn!("Unknown scenario: {id}");
+                simulation.start_new();
+            }
+        }
+
+        cmd.insert_resource(simulation);
+        return;
+    }
+
+    match saved_game.to_restore(&game_start) {
+        Some(snapshot) => {
+            snapshot.restore(&mut simulation);
+
+            let seconds = offline_seconds(
+                snapshot.saved_at,
+                chrono::Utc::now().timestamp(),
+                settings.offline_hours.0,
+            );
+
+            if seconds > 0. {
+                let sparks = simulation.fast_forward(seconds);
+                cmd.insert_resource(OfflineProgress { seconds, sparks });
+            }
+        }
+        None => simulation.start_new(),
+    }
+
+    cmd.insert_resource(simulation);
+}
+
+fn remove_simulation(mut cmd: Commands) {
+    cmd.remove_resource::<SparkSimulation>();
+}
+
+fn sync_catalog(
+    catalog: Res<NodeCatalog>,
+    prestige_progress: Res<PrestigeProgress>,
+    game_start: Res<GameStart>,
+    mut simulation: ResMut<SparkSimulation>,
+) {
+    if catalog.is_changed() {
+        simulation.set_definitions(catalog.nodes.clone());
+        simulation.set_upgrades(catalog.upgrades.clone());
+    }
+
+    // Prestige bonuses do not carry over into scenarios
+    if matches!(*game_start, GameStart::Scenario(_)) {
+        return;
+    }
+
+    if catalog.is_changed() || prestige_progress.is_changed() {
+        simulation.set_permanent_effects(prestige_progress.effects(&catalog));
+    }
+}
+
+/// Sends the parts of an outcome on as their own events
+#[derive(SystemParam)]
+struct SimulationWriters<'w> {
+    events: EventWriter<'w, SimulationEvent>,
+    earned: EventWriter<'w, SparksEarned>,
+    spent: EventWriter<'w, SparksSpent>,
+    triggered: EventWriter<'w, NodeTriggered>,
+}
+
+impl SimulationWriters<'_> {
+    fn write(&mut self, outcome: SimulationOutcome) {
+        self.events.write_batch(outcome.events);
+        self.earned.write_batch(outcome.earned);
+        self.spent.write_batch(outcome.spent);
+        self.triggered.write_batch(outcome.triggered);
+    }
+}
+
+/// The one place requested actions are validated and applied. Rejected
+/// actions change nothing and send no events.
+fn apply_actions(
+    mut simulation: ResMut<SparkSimulation>,
+    mut simulation_actions: EventReader<SimulationAction>,
+    mut build_requests: EventReader<BuildRequested>,
+    mut writers: SimulationWriters,
+) {
+    for action in simulation_actions.read() {
+        match simulation.apply(action.clone()) {
+            Ok(outcome) => writers.write(outcome),
+            Err(e) => debug!("Could not apply {action:?}: {e}"),
+        }
+    }
+
+    for request in build_requests.read() {
+        match simulation.build_facing(request) {
+            Ok(outcome) => writers.write(outcome),
+            Err(e) => debug!("Could not apply {request:?}: {e}"),
+        }
+    }
+}
+
+fn step_simulation(
+    time: Res<Time>,
+    mut simulation: ResMut<SparkSimulation>,
+    mut writers: SimulationWriters,
+) {
+    let outcome = simulation.step(time.delta_secs());
+    writers.write(outcome);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{
+        catalog::{CostCurve, NodeShape, TriggerPattern},
+        pulses::{MAX_CHAIN_DEPTH, PULSE_HOP_DELAY},
+    };
+
+    const CLICKER: IVec2 = IVec2::new(4, 4);
+    const EAST: IVec2 = IVec2::new(5, 4);
+
+    fn definition(
+        id: &str,
+        yields: f64,
+        pulse_interval: Option<f32>,
+        trigger_pattern: TriggerPattern,
+    ) -> NodeDefinition {
+        NodeDefinition {
+            id: id.to_string(),
+            i18n_key: id.to_string(),
+            description_key: id.to_string(),
+            cost: SparkAmount::from(10.),
+            cost_curve: CostCurve::Flat,
+            refund_percentage: 50.,
+            yields: SparkAmount::from(yields),
+            pulse_interval,
+            pulse_delay: None,
+            trigger_pattern,
+            shape: NodeShape::Circle { radius: 1. },
+            color: [1.; 4],
+            buildable: true,
+        }
+    }
+
+    /// A 9x9 board with a clicker pulsing every second in the middle
+    fn simulation() -> SparkSimulation {
+        let mut simulation = SparkSimulation::new(
+            vec![
+                definition(
+                    INITIAL_NODE,
+                    1.,
+                    Some(1.),
+                    TriggerPattern::Orthogonal,
+                ),
+                definition("relay", 1., None, TriggerPattern::Orthogonal),
+                definition("wall", 0., None, TriggerPattern::None),
+                // Passes pulses on to the next cell to the east
+                definition(
+                    "wire",
+                    1.,
+                    None,
+                    TriggerPattern::Custom(vec![(1, 0)]),
+                ),
+                NodeDefinition {
+                    buildable: false,
+                    ..definition("core", 1., None, TriggerPattern::None)
+                },
+            ],
+            Vec::new(),
+        );
+        simulation.start_new();
+        simulation.bounds = GridBounds {
+            min_x: 0,
+            min_y: 0,
+            max_x: 8,
+            max_y: 8,
+        };
+        simulation
+    }
+
+    fn with_sparks(amount: f64) -> SparkSimulation {
+        let mut simulation = simulation();
+        simulation.sparks.0 = SparkAmount::from(amount);
+        simulation
+    }
+
+    fn build(kind: &str, position: IVec2) -> SimulationAction {
+        SimulationAction::Build {
+            kind: kind.to_string(),
+            position,
+        }
+    }
+
+    fn pulses(outcome: &SimulationOutcome) -> Vec<(IVec2, u32)> {
+        outcome
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                SimulationEvent::Pulsed {
+                    position, depth, ..
+                } => Some((*position, *depth)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn nodes_pulse_on_their_interval() {
+        let mut simulation = simulation();
+        simulation.insert_node("relay", EAST);
+
+        assert!(pulses(&simulation.step(0.5)).is_empty());
+
+        let outcome = simulation.step(0.5);
+        assert_eq!(
+            outcome.events,
+            vec![SimulationEvent::Pulsed {
+                position: CLICKER,
+                targets: vec![EAST],
+                depth: 1,
+                delay: PULSE_HOP_DELAY,
+            }]
+        );
+    }
+
+    #[test]
+    fn nodes_without_targets_do_not_pulse() {
+        let mut simulation = simulation();
+
+        assert!(simulation.step(1.).events.is_empty());
+    }
+
+    #[test]
+    fn pulses_arrive_after_the_hop_delay() {
+        let mut simulation = simulation();
+        simulation.insert_node("relay", EAST);
+        simulation.step(1.);
+
+        let early = simulation.step(PULSE_HOP_DELAY * 0.5);
+        assert!(early.triggered.is_empty());
+        assert!(early.earned.is_empty());
+
+        let arrived = simulation.step(PULSE_HOP_DELAY);
+        assert_eq!(
+            arrived.triggered,
+            vec![NodeTriggered {
+                source: CLICKER,
+                target: EAST,
+                depth: 1,
+            }]
+        );
+        assert_eq!(
+            arrived.earned,
+            vec![SparksEarned {
+                amount: SparkAmount::from(1.),
+                from: EarnedFrom::Trigger {
+                    position: EAST,
+                    source_kind: INITIAL_NODE.to_string(),
+                },
+            }]
+        );
+        assert_eq!(simulation.sparks.0, SparkAmount::from(1.));
+    }
+
+    #[test]
+    fn triggered_nodes_pass_pulses_on() {
+        let mut simulation = simulation();
+        simulation.insert_node("relay", EAST);
+        simulation.step(1.);
+
+        let relayed = simulation.step(PULSE_HOP_DELAY * 1.5);
+        assert_eq!(pulses(&relayed), vec![(EAST, 2)]);
+    }
+
+    #[test]
+    fn cooling_down_nodes_do_not_pulse() {
+        let mut simulation = simulation();
+        simulation.insert_node("relay", EAST);
+        simulation.step(1.);
+        simulation.step(PULSE_HOP_DELAY * 1.5);
+
+        // The pulse the relay sent back reaches the clicker within its
+        // cooldown
+        let returned = simulation.step(PULSE_HOP_DELAY * 1.5);
+        assert_eq!(
+            returned.triggered,
+            vec![NodeTriggered {
+                source: EAST,
+                target: CLICKER,
+                depth: 2,
+            }]
+        );
+        assert!(pulses(&returned).is_empty());
+    }
+
+    #[test]
+    fn nodes_pulse_again_after_the_cooldown() {
+        let mut simulation = simulation();
+        simulation.insert_node("relay", EAST);
+
+        let mut clicker_pulses = 0;
+        for _ in 0..128 {
+            clicker_pulses += pulses(&simulation.step(1. / 32.))
+                .iter()
+                .filter(|(position, _)| *position == CLICKER)
+                .count();
+        }
+
+        // Once a second on its own, the pulses it gets back arrive before
+        // the cooldown is over
+        assert_eq!(clicker_pulses, 4);
+    }
+
+    #[test]
+    fn chains_stop_at_the_maximum_depth() {
+        let mut simulation = simulation();
+        for x in 5..30 {
+            simulation.insert_node("wire", IVec2::new(x, 4));
+        }
+
+        let mut deepest = 0;
+        let mut reached = 0;
+        for _ in 0..200 {
+            for triggered in simulation.step(0.05).triggered {
+                deepest = deepest.max(triggered.depth);
+                reached = reached.max(triggered.target.x);
+            }
+        }
+
+        assert_eq!(deepest, MAX_CHAIN_DEPTH);
+        assert_eq!(reached, CLICKER.x + MAX_CHAIN_DEPTH as i32);
+        assert_eq!(simulation.scheduler.deepest_chain, MAX_CHAIN_DEPTH);
+    }
+
+    #[test]
+    fn clicking_yields_sparks() {
+        let mut simulation = simulation();
+
+        let outcome = simulation
+            .apply(SimulationAction::Click { position: CLICKER })
+            .unwrap();
+
+        assert_eq!(
+            outcome.events,
+            vec![SimulationEvent::Clicked { position: CLICKER }]
+        );
+        assert_eq!(
+            outcome.earned,
+            vec![SparksEarned {
+                amount: SparkAmount::from(1.),
+                from: EarnedFrom::Click {
+                    position: CLICKER,
+                    kind: INITIAL_NODE.to_string(),
+                },
+            }]
+        );
+        assert_eq!(simulation.sparks.0, SparkAmount::from(1.));
+        assert_eq!(simulation.lifetime_sparks, SparkAmount::from(1.));
+    }
+
+    #[test]
+    fn clicking_nodes_without_yield() {
+        let mut simulation = simulation();
+        simulation.insert_node("wall", EAST);
+
+        assert_eq!(
+            simulation.apply(SimulationAction::Click { position: EAST }),
+            Ok(SimulationOutcome::default())
+        );
+        assert_eq!(
+            simulation.apply(SimulationAction::Click {
+                position: IVec2::ZERO
+            }),
+            Err(SimulationError::Empty(IVec2::ZERO))
+        );
+    }
+
+    #[test]
+    fn building_spends_sparks() {
+        let mut simulation = with_sparks(15.);
+
+        let outcome = simulation.apply(build("relay", EAST)).unwrap();
+
+        assert_eq!(
+            outcome.events,
+            vec![SimulationEvent::Built {
+                kind: "relay".to_string(),
+                position: EAST,
+                cost: SparkAmount::from(10.),
+            }]
+        );
+        assert_eq!(
+            outcome.spent,
+            vec![SparksSpent {
+                amount: SparkAmount::from(10.),
+                on: SpentOn::Build,
+            }]
+        );
+        assert_eq!(simulation.sparks.0, SparkAmount::from(5.));
+        assert_eq!(simulation.owned("relay"), 1);
+    }
+
+    #[test]
+    fn building_errors() {
+        let mut simulation = with_sparks(100.);
+
+        assert_eq!(
+            simulation.apply(build("relay", CLICKER)),
+            Err(SimulationError::Occupied(CLICKER))
+        );
+        assert_eq!(
+            simulation.apply(build("relay", IVec2::new(9, 4))),
+            Err(SimulationError::OutOfBounds(IVec2::new(9, 4)))
+        );
+        assert_eq!(
+            simulation.apply(build("relay", IVec2::new(-1, 0))),
+            Err(SimulationError::OutOfBounds(IVec2::new(-1, 0)))
+        );
+        assert_eq!(
+            simulation.apply(build("lamp", EAST)),
+            Err(SimulationError::UnknownNode("lamp".to_string()))
+        );
+        assert_eq!(
+            simulation.apply(build("core", EAST)),
+            Err(SimulationError::NotBuildable("core".to_string()))
+        );
+
+        simulation.rules.blocked.insert(EAST);
+        assert_eq!(
+            simulation.apply(build("relay", EAST)),
+            Err(SimulationError::Blocked(EAST))
+        );
+
+        // Nothing was spent on the failed attempts
+        assert_eq!(simulation.sparks.0, SparkAmount::from(100.));
+    }
+
+    #[test]
+    fn building_without_enough_sparks() {
+        let mut simulation = with_sparks(9.);
+
+        assert_eq!(
+            simulation.apply(build("relay", EAST)),
+            Err(SimulationError::InsufficientSparks {
+                cost: SparkAmount::from(10.)
+            })
+        );
+        assert_eq!(simulation.sparks.0, SparkAmount::from(9.));
+        assert!(!simulation.nodes.contains_key(&EAST));
+    }
+
+    #[test]
+    fn selling_refunds_part_of_the_price() {
+        let mut simulation = simulation();
+        simulation.insert_node("relay", EAST);
+
+        let outcome = simulation
+            .apply(SimulationAction::Sell { position: EAST })
+            .unwrap();
+
+        assert_eq!(
+            outcome.events,
+            vec![SimulationEvent::Sold {
+                kind: "relay".to_string(),
+                position: EAST,
+                facing: GridDirection::North,
+                refund: SparkAmount::from(5.),
+            }]
+        );
+        assert_eq!(simulation.sparks.0, SparkAmount::from(5.));
+        // Refunds are not earnings
+        assert_eq!(simulation.lifetime_sparks, SparkAmount::ZERO);
+        assert!(!simulation.nodes.contains_key(&EAST));
+    }
+
+    #[test]
+    fn selling_the_last_producer() {
+        let mut simulation = simulation();
+        simulation.insert_node("wall", EAST);
+
+        assert_eq!(
+            simulation.apply(SimulationAction::Sell { position: CLICKER }),
+            Err(SimulationError::LastProducer(CLICKER))
+        );
+        assert!(simulation
+            .apply(SimulationAction::Sell { position: EAST })
+            .is_ok());
+        assert_eq!(
+            simulation.apply(SimulationAction::Sell { position: EAST }),
+            Err(SimulationError::Empty(EAST))
+        );
+    }
+
+    #[test]
+    fn moving_nodes() {
+        let mut simulation = simulation();
+        simulation.insert_node("relay", EAST);
+        let to = IVec2::new(0, 0);
+
+        assert_eq!(
+            simulation.apply(SimulationAction::Move { from: CLICKER, to }),
+            Ok(SimulationOutcome::event(SimulationEvent::Moved {
+                from: CLICKER,
+                to
+            }))
+        );
+        assert!(!simulation.nodes.contains_key(&CLICKER));
+        assert_eq!(simulation.nodes[&to].kind, INITIAL_NODE);
+
+        assert_eq!(
+            simulation.apply(SimulationAction::Move { from: to, to: EAST }),
+            Err(SimulationError::Occupied(EAST))
+        );
+        assert_eq!(
+            simulation.apply(SimulationAction::Move {
+                from: to,
+                to: IVec2::new(0, 9)
+            }),
+            Err(SimulationError::OutOfBounds(IVec2::new(0, 9)))
+        );
+        assert_eq!(
+            simulation.apply(SimulationAction::Move {
+                from: CLICKER,
+                to: IVec2::new(1, 1)
+            }),
+            Err(SimulationError::Empty(CLICKER))
+        );
+    }
+
+    #[test]
+    fn swapping_nodes() {
+        let mut simulation = simulation();
+        simulation.insert_node("relay", EAST);
+
+        assert_eq!(
+            simulation.apply(SimulationAction::Swap {
+                first: CLICKER,
+                second: EAST
+            }),
+            Ok(SimulationOutcome::event(SimulationEvent::Swapped {
+                first: CLICKER,
+                second: EAST
+            }))
+        );
+        assert_eq!(simulation.nodes[&CLICKER].kind, "relay");
+        assert_eq!(simulation.nodes[&EAST].kind, INITIAL_NODE);
+
+        let empty = IVec2::new(0, 0);
+        assert_eq!(
+            simulation.apply(SimulationAction::Swap {
+                first: CLICKER,
+                second: empty
+            }),
+            Err(SimulationError::Empty(empty))
+        );
+        assert_eq!(simulation.nodes.len(), 2);
+    }
+
+    /// A relay next to the clicker earns sparks every second
+    fn producing() -> SparkSimulation {
+        let mut simulation = simulation();
+        simulation.insert_node("relay", EAST);
+        simulation
+    }
+
+    #[test]
+    fn fast_forward_earns_like_playing() {
+        let mut fast = producing();
+        let mut played = producing();
+
+        let earned = fast.fast_forward(60.);
+        for _ in 0..60 * 32 {
+            played.step(1. / 32.);
+        }
+
+        assert!(!earned.is_zero());
+        assert_eq!(earned, fast.sparks.0);
+        assert_eq!(fast.lifetime_sparks, fast.sparks.0);
+        // Coarse steps may miss pulses arriving within a cooldown
+        assert!(earned <= played.sparks.0);
+    }
+
+    #[test]
+    fn fast_forward_caps_the_steps() {
+        let simulated = FAST_FORWARD_STEP * FAST_FORWARD_MAX_STEPS as f32;
+        let mut capped = producing();
+        let mut doubled = producing();
+
+        let earned = capped.fast_forward(simulated);
+        let extrapolated = doubled.fast_forward(simulated * 2.);
+
+        assert_eq!(doubled.scheduler.elapsed, capped.scheduler.elapsed);
+        assert!(doubled.scheduler.elapsed <= simulated + FAST_FORWARD_STEP);
+        assert_eq!(
+            extrapolated,
+            earned.checked_mul(SparkAmount::from(2.)).unwrap()
+        );
+        assert_eq!(doubled.sparks.0, extrapolated);
+        assert_eq!(doubled.lifetime_sparks, extrapolated);
+    }
+
+    fn facing_node() -> NodeDefinition {
+        definition("lamp", 1., None, TriggerPattern::Facing { range: 2 })
+    }
+
+    #[test]
+    fn build_requests_turn_the_node() {
+        let mut simulation = with_sparks(100.);
+        simulation.set_definitions(vec![facing_node()]);
+
+        let outcome = simulation
+            .build_facing(&BuildRequested {
+                kind: "lamp".to_string(),
+                position: EAST,
+                facing: GridDirection::SouthWest,
+            })
+            .unwrap();
+
+        // Three turns the short way round
+        assert_eq!(outcome.events.len(), 4);
+        assert_eq!(
+            outcome.events.last(),
+            Some(&SimulationEvent::Rotated {
+                position: EAST,
+                facing: GridDirection::SouthWest,
+            })
+        );
+        assert_eq!(simulation.nodes[&EAST].facing, GridDirection::SouthWest);
+    }
+
+    #[test]
+    fn build_requests_for_nodes_without_direction() {
+        let mut simulation = with_sparks(100.);
+
+        let outcome = simulation
+            .build_facing(&BuildRequested {
+                kind: "relay".to_string(),
+                position: EAST,
+                facing: GridDirection::East,
+            })
+            .unwrap();
+
+        assert_eq!(outcome.events.len(), 1);
+        assert_eq!(simulation.nodes[&EAST].facing, GridDirection::North);
+    }
+
+    #[test]
+    fn undo_and_redo_a_build() {
+        let mut simulation = with_sparks(15.);
+        let built = simulation.apply(build("relay", EAST)).unwrap();
+        let action = ReversibleAction::from_event(&built.events[0]).unwrap();
+
+        let undone = simulation
+            .apply(SimulationAction::Undo(action.clone()))
+            .unwrap();
+        assert_eq!(
+            undone.events,
+            vec![SimulationEvent::Undone(action.clone())]
+        );
+        assert_eq!(
+            undone.earned,
+            vec![SparksEarned {
+                amount: SparkAmount::from(10.),
+                from: EarnedFrom::Refund,
+            }]
+        );
+        assert_eq!(simulation.sparks.0, SparkAmount::from(15.));
+        assert!(!simulation.nodes.contains_key(&EAST));
+
+        let redone = simulation
+            .apply(SimulationAction::Redo(action.clone()))
+            .unwrap();
+        assert_eq!(redone.events, vec![SimulationEvent::Redone(action)]);
+        assert_eq!(redone.spent, built.spent);
+        assert_eq!(simulation.sparks.0, SparkAmount::from(5.));
+        assert!(simulation.nodes.contains_key(&EAST));
+    }
+
+    #[test]
+    fn undo_a_sale() {
+        let mut simulation = simulation();
+        simulation.set_definitions(vec![
+            definition(INITIAL_NODE, 1., Some(1.), TriggerPattern::Orthogonal),
+            facing_node(),
+        ]);
+        simulation.insert_node("lamp", EAST);
+        if let Some(node) = simulation.nodes.get_mut(&EAST) {
+            node.facing = GridDirection::West;
+        }
+
+        let sold = simulation
+            .apply(SimulationAction::Sell { position: EAST })
+            .unwrap();
+        let action = ReversibleAction::from_event(&sold.events[0]).unwrap();
+
+        let undone = simulation.apply(SimulationAction::Undo(action)).unwrap();
+        assert_eq!(
+            undone.spent,
+            vec![SparksSpent {
+                amount: SparkAmount::from(5.),
+                on: SpentOn::Buyback,
+            }]
+        );
+        assert_eq!(simulation.sparks.0, SparkAmount::ZERO);
+        assert_eq!(simulation.nodes[&EAST].facing, GridDirection::West);
+    }
+
+    #[test]
+    fn undo_fails_on_a_changed_board() {
+        let mut simulation = simulation();
+        let action = ReversibleAction::Build {
+            kind: "relay".to_string(),
+            position: EAST,
+            cost: SparkAmount::from(10.),
+        };
+
+        assert_eq!(
+            simulation.apply(SimulationAction::Undo(action)),
+            Err(SimulationError::Empty(EAST))
+        );
+        assert_eq!(simulation.sparks.0, SparkAmount::ZERO);
+    }
+}
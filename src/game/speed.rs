@@ -0,0 +1,162 @@
+use bevy::{prelude::*, render::view::RenderLayers, ui::ContentSize};
+
+use crate::{
+    canvas::{
+        text::{SyltText, SyltTextAlign, SyltTextStyle},
+        ui_canvas::SyltUiText,
+    },
+    game::{
+        build::ShowBuildMenu,
+        node_menu::NodeMenu,
+        system_set::{SyltGameSystemSet, SyltPausableSystems},
+        upgrades::UpgradePanel,
+    },
+    i18n::SyltI18nText,
+    routes::SyltRouterState,
+    ui::constants::SU4,
+};
+
+/// Hotkeys for the game speeds, 0 stops the clock
+const SPEED_KEYS: [(KeyCode, f32); 4] = [
+    (KeyCode::Digit1, 1.),
+    (KeyCode::Digit2, 2.),
+    (KeyCode::Digit3, 5.),
+    (KeyCode::Digit4, 10.),
+];
+const STOP_KEY: KeyCode = KeyCode::Digit0;
+
+pub struct SpeedPlugin;
+
+impl Plugin for SpeedPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(SyltRouterState::Game), spawn_speed_text);
+
+        app.add_systems(OnExit(SyltRouterState::Game), reset_speed);
+
+        app.add_systems(
+            Update,
+            on_keyboard_press
+                .in_set(SyltPausableSystems)
+                .in_set(SyltGameSystemSet::Input)
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<ShowBuildMenu>)
+                        .and(resource_exists::<NodeMenu>)
+                        .and(resource_exists::<UpgradePanel>),
+                ),
+        );
+
+        app.add_systems(
+            Update,
+            update_speed_text.run_if(in_state(SyltRouterState::Game)),
+        );
+    }
+}
+
+#[derive(Component)]
+struct SpeedLabel;
+
+#[derive(Component)]
+struct SpeedText;
+
+fn spawn_speed_text(mut cmd: Commands) {
+    let text = || {
+        (
+            RenderLayers::layer(1),
+            SyltUiText,
+            SyltTextStyle {
+                font_size: 28.,
+                ..default()
+            },
+            SyltTextAlign::Middle,
+            SyltText::default(),
+            Node::default(),
+            ContentSize::default(),
+        )
+    };
+
+    cmd.spawn((
+        StateScoped(SyltRouterState::Game),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.),
+            top: Val::Px(SU4),
+            display: Display::Flex,
+            justify_content: JustifyContent::Center,
+            column_gap: Val::Px(SU4),
+            ..default()
+        },
+        Pickable::IGNORE,
+    ))
+    .with_children(|parent| {
+        parent.spawn((text(), SpeedLabel, SyltI18nText::from_key("speed")));
+        parent.spawn((text(), SpeedText));
+    });
+}
+
+fn reset_speed(mut time: ResMut<Time<Virtual>>) {
+    time.set_relative_speed(1.);
+    time.unpause();
+}
+
+/// 0 stops the game clock and 1-4 run it at one of the [`SPEED_KEYS`].
+/// Nothing happens in the frame a panel closes, the same digit may have
+/// picked an item in it.
+fn on_keyboard_press(
+    display_shop: Res<ShowBuildMenu>,
+    node_menu: Res<NodeMenu>,
+    upgrade_panel: Res<UpgradePanel>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if display_shop.0 || *node_menu != NodeMenu::Closed || upgrade_panel.open {
+        return;
+    }
+
+    if display_shop.is_changed()
+        || node_menu.is_changed()
+        || upgrade_panel.is_changed()
+    {
+        return;
+    }
+
+    if keyboard_input.just_pressed(STOP_KEY) {
+        if time.is_paused() {
+            time.unpause();
+        } else {
+            time.pause();
+        }
+        return;
+    }
+
+    for (key, speed) in SPEED_KEYS {
+        if keyboard_input.just_pressed(key) {
+            time.set_relative_speed(speed);
+            time.unpause();
+        }
+    }
+}
+
+fn update_speed_text(
+    time: Res<Time<Virtual>>,
+    mut label: Single<&mut SyltI18nText, With<SpeedLabel>>,
+    mut text: Single<&mut SyltText, (With<SpeedText>, Without<SpeedLabel>)>,
+) {
+    let content = if time.is_paused() {
+        String::new()
+    } else {
+        format!("{}x", time.relative_speed())
+    };
+
+    // Changing the key translates the label again, only do it when needed
+    if text.content == content {
+        return;
+    }
+
+    label.update_key(if time.is_paused() {
+        "speed stopped"
+    } else {
+        "speed"
+    });
+    text.content = content;
+}
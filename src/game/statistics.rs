@@ -0,0 +1,333 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+
+use crate::{
+    game::{
+        currency::SparkAmount,
+        save::{GameStart, SaveGameEvent, SavedGame},
+        simulation::{
+            EarnedFrom, NodeTriggered, ReversibleAction, SimulationEvent,
+            SparkSimulation, SparksEarned, SparksSpent, SpentOn,
+        },
+        system_set::SyltPausableSystems,
+    },
+    routes::SyltRouterState,
+    signals::SyltSignal,
+};
+
+const STATISTICS_FILE: &str = "statistics.yaml";
+
+pub struct StatisticsPlugin;
+
+impl Plugin for StatisticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameStatistics>();
+
+        app.add_systems(Startup, trigger_load_statistics);
+
+        app.add_systems(Update, handle_statistics_loaded);
+
+        app.add_systems(OnEnter(SyltRouterState::Game), restore_statistics);
+
+        app.add_systems(OnExit(SyltRouterState::Game), save_statistics);
+
+        app.add_systems(
+            Update,
+            save_statistics.run_if(
+                in_state(SyltRouterState::Game).and(on_event::<SaveGameEvent>),
+            ),
+        );
+
+        app.add_systems(
+            Update,
+            (record_statistics, count_play_time)
+                .in_set(SyltPausableSystems)
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<SparkSimulation>),
+                ),
+        );
+    }
+}
+
+/// Counters for one stretch of play
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Statistics {
+    /// Nodes clicked by the player
+    pub clicks: u64,
+    /// Pulses that reached a node
+    pub triggers: u64,
+    /// Sparks earned, by node type
+    pub earned: BTreeMap<String, SparkAmount>,
+    /// Sparks spent on nodes, cells and upgrades
+    pub spent: SparkAmount,
+    pub built: u64,
+    pub sold: u64,
+    /// Deepest pulse chain
+    pub longest_chain: u32,
+    /// Seconds played, pauses excluded
+    pub play_time: f64,
+}
+
+impl Statistics {
+    /// Undone actions no longer count, redoing them counts them again
+    pub fn record(&mut self, event: &SimulationEvent) {
+        match event {
+            SimulationEvent::Clicked { .. } => self.clicks += 1,
+            SimulationEvent::Built { .. }
+            | SimulationEvent::Redone(ReversibleAction::Build { .. }) => {
+                self.built += 1;
+            }
+            SimulationEvent::Sold { .. }
+            | SimulationEvent::Redone(ReversibleAction::Sell { .. }) => {
+                self.sold += 1;
+            }
+            SimulationEvent::Undone(ReversibleAction::Build { .. }) => {
+                self.built = self.built.saturating_sub(1);
+            }
+            SimulationEvent::Undone(ReversibleAction::Sell { .. }) => {
+                self.sold = self.sold.saturating_sub(1);
+            }
+            _ => {}
+        }
+    }
+
+    /// Counts generated sparks by the type of the node they are credited
+    /// to, refunds of undone purchases take back what was spent
+    pub fn record_earned(&mut self, earned: &SparksEarned) {
+        if earned.from == EarnedFrom::Refund {
+            self.spent =
+                self.spent.checked_sub(earned.amount).unwrap_or_default();
+            return;
+        }
+
+        if let Some(kind) = earned.node_kind() {
+            *self.earned.entry(kind.to_string()).or_default() += earned.amount;
+        }
+    }
+
+    /// Buying back a sold node undoes the sale, it is not spending
+    pub fn record_spent(&mut self, spent: &SparksSpent) {
+        if spent.on != SpentOn::Buyback {
+            self.spent += spent.amount;
+        }
+    }
+
+    pub fn record_triggered(&mut self, triggered: &NodeTriggered) {
+        self.triggers += 1;
+        self.longest_chain = self.longest_chain.max(triggered.depth);
+    }
+}
+
+/// Statistics of the running game. `lifetime` covers every game and is saved
+/// in its own file, `game` is saved with the game and `session` starts over
+/// every time the game is entered.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct GameStatistics {
+    pub lifetime: Statistics,
+    pub game: Statistics,
+    pub session: Statistics,
+}
+
+impl GameStatistics {
+    fn all_mut(&mut self) -> [&mut Statistics; 3] {
+        [&mut self.lifetime, &mut self.game, &mut self.session]
+    }
+}
+
+fn restore_statistics(
+    mut statistics: ResMut<GameStatistics>,
+    game_start: Res<GameStart>,
+    saved_game: Res<SavedGame>,
+) {
+    statistics.game = saved_game
+        .to_restore(&game_start)
+        .map(|snapshot| snapshot.statistics.clone())
+        .unwrap_or_default();
+    statistics.session = Statistics::default();
+}
+
+fn record_statistics(
+    mut simulation_events: EventReader<SimulationEvent>,
+    mut sparks_earned: EventReader<SparksEarned>,
+    mut sparks_spent: EventReader<SparksSpent>,
+    mut node_triggered: EventReader<NodeTriggered>,
+    mut statistics: ResMut<GameStatistics>,
+) {
+    for event in simulation_events.read() {
+        for counters in statistics.all_mut() {
+            counters.record(event);
+        }
+    }
+
+    for earned in sparks_earned.read() {
+        for counters in statistics.all_mut() {
+            counters.record_earned(earned);
+        }
+    }
+
+    for spent in sparks_spent.read() {
+        for counters in statistics.all_mut() {
+            counters.record_spent(spent);
+        }
+    }
+
+    for triggered in node_triggered.read() {
+        for counters in statistics.all_mut() {
+            counters.record_triggered(triggered);
+        }
+    }
+}
+
+fn count_play_time(time: Res<Time>, mut statistics: ResMut<GameStatistics>) {
+    let delta = time.delta_secs_f64();
+
+    for counters in statistics.all_mut() {
+        counters.play_time += delta;
+    }
+}
+
+/// Written whenever the game is saved, scenarios included
+fn save_statistics(
+    statistics: Res<GameStatistics>,
+    mut sylt_signal_writer: EventWriter<SyltSignal>,
+) {
+    match serde_yaml::to_string(&statistics.lifetime) {
+        Ok(data) => {
+            sylt_signal_writer.write(SyltSignal::SaveFile {
+                key: STATISTICS_FILE.into(),
+                data: data.into(),
+            });
+        }
+        Err(e) => warn!("Could not serialize the statistics: {e}"),
+    }
+}
+
+fn trigger_load_statistics(mut sylt_signal_writer: EventWriter<SyltSignal>) {
+    sylt_signal_writer.write(SyltSignal::LoadFile {
+        key: STATISTICS_FILE.into(),
+    });
+}
+
+fn handle_statistics_loaded(
+    mut sylt_signal_reader: EventReader<SyltSignal>,
+    mut statistics: ResMut<GameStatistics>,
+) {
+    for event in sylt_signal_reader.read() {
+        if let SyltSignal::FileLoaded { key, data } = event {
+            if key.to_string() != STATISTICS_FILE {
+                continue;
+            }
+
+            match serde_yaml::from_str::<Statistics>(data) {
+                Ok(loaded) => statistics.lifetime = loaded,
+                Err(e) => warn!("Could not parse the statistics: {e}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(cost: f64) -> ReversibleAction {
+        ReversibleAction::Build {
+            kind: "relay".to_string(),
+            position: IVec2::new(1, 1),
+            cost: SparkAmount::from(cost),
+        }
+    }
+
+    #[test]
+    fn undone_builds_are_refunded() {
+        let mut statistics = Statistics::default();
+        let cost = SparkAmount::from(10.);
+
+        // Build
+        statistics.record(&SimulationEvent::Built {
+            kind: "relay".to_string(),
+            position: IVec2::new(1, 1),
+            cost,
+        });
+        statistics.record_spent(&SparksSpent {
+            amount: cost,
+            on: SpentOn::Build,
+        });
+
+        // Undo
+        statistics.record(&SimulationEvent::Undone(build(10.)));
+        statistics.record_earned(&SparksEarned {
+            amount: cost,
+            from: EarnedFrom::Refund,
+        });
+        assert_eq!(statistics.built, 0);
+        assert_eq!(statistics.spent, SparkAmount::ZERO);
+
+        // Redo
+        statistics.record(&SimulationEvent::Redone(build(10.)));
+        statistics.record_spent(&SparksSpent {
+            amount: cost,
+            on: SpentOn::Build,
+        });
+        assert_eq!(statistics.built, 1);
+        assert_eq!(statistics.spent, cost);
+    }
+
+    #[test]
+    fn undone_sales_are_not_spending() {
+        let mut statistics = Statistics {
+            sold: 1,
+            ..default()
+        };
+
+        statistics.record(&SimulationEvent::Undone(ReversibleAction::Sell {
+            kind: "relay".to_string(),
+            position: IVec2::new(1, 1),
+            facing: default(),
+            refund: SparkAmount::from(5.),
+        }));
+        statistics.record_spent(&SparksSpent {
+            amount: SparkAmount::from(5.),
+            on: SpentOn::Buyback,
+        });
+
+        assert_eq!(statistics.sold, 0);
+        assert_eq!(statistics.spent, SparkAmount::ZERO);
+    }
+
+    #[test]
+    fn earnings_are_credited_to_the_pulsing_node() {
+        let mut statistics = Statistics::default();
+
+        // The clicker yields, the stimulator pulsing it gets the credit
+        statistics.record_earned(&SparksEarned {
+            amount: SparkAmount::from(2.),
+            from: EarnedFrom::Trigger {
+                position: IVec2::new(1, 1),
+                source_kind: "stimulator".to_string(),
+            },
+        });
+        statistics.record_earned(&SparksEarned {
+            amount: SparkAmount::from(1.),
+            from: EarnedFrom::Click {
+                position: IVec2::new(1, 1),
+                kind: "clicker".to_string(),
+            },
+        });
+        statistics.record_earned(&SparksEarned {
+            amount: SparkAmount::from(5.),
+            from: EarnedFrom::Sale,
+        });
+
+        assert_eq!(
+            statistics.earned,
+            BTreeMap::from([
+                ("clicker".to_string(), SparkAmount::from(1.)),
+                ("stimulator".to_string(), SparkAmount::from(2.)),
+            ])
+        );
+    }
+}
@@ -49,5 +49,9 @@ impl Plugin for SyltSystemSetPlugin {
             Update,
             SyltPausableSystems.run_if(in_state(SyltGamePauseState(false))),
         );
+        app.configure_sets(
+            FixedUpdate,
+            SyltPausableSystems.run_if(in_state(SyltGamePauseState(false))),
+        );
     }
 }
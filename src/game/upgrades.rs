@@ -0,0 +1,506 @@
+use bevy::{prelude::*, render::view::RenderLayers, ui::ContentSize};
+
+use crate::{
+    canvas::{
+        text::{SyltText, SyltTextAlign, SyltTextStyle},
+        ui_canvas::{SyltUiScene, SyltUiText},
+    },
+    game::{
+        build::ShowBuildMenu,
+        catalog::NodeDefinition,
+        currency::SparkAmount,
+        node_menu::NodeMenu,
+        simulation::{SimulationAction, SimulationEvent, SparkSimulation},
+        system_set::{SyltGameSystemSet, SyltPausableSystems},
+    },
+    i18n::SyltI18nText,
+    menus::SyltMenuState,
+    routes::SyltRouterState,
+    settings::SyltSettings,
+    ui::constants::{SU2, SU4},
+};
+
+pub struct UpgradesPlugin;
+
+impl Plugin for UpgradesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(SyltRouterState::Game), add_upgrade_panel);
+
+        app.add_systems(OnExit(SyltRouterState::Game), remove_upgrade_panel);
+
+        app.add_systems(
+            Update,
+            (on_keyboard_press)
+                .in_set(SyltPausableSystems)
+                .in_set(SyltGameSystemSet::Input)
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<UpgradePanel>)
+                        .and(resource_exists::<ShowBuildMenu>)
+                        .and(resource_exists::<NodeMenu>)
+                        .and(resource_exists::<SparkSimulation>),
+                ),
+        );
+
+        app.add_systems(
+            Update,
+            (sync_upgrade_panel, draw_upgrade_rows)
+                .chain()
+                .in_set(SyltPausableSystems)
+                .in_set(SyltGameSystemSet::Update)
+                .run_if(
+                    in_state(SyltRouterState::Game)
+                        .and(resource_exists::<UpgradePanel>)
+                        .and(resource_exists::<SparkSimulation>),
+                ),
+        );
+    }
+}
+
+/// A one time purchase, as described in `assets/nodes.ron`
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct UpgradeDefinition {
+    pub id: String,
+    pub i18n_key: String,
+    pub description_key: String,
+    pub cost: SparkAmount,
+    /// Ids of the upgrades that have to be bought first
+    #[serde(default)]
+    pub requires: Vec<String>,
+    pub effects: Vec<UpgradeEffect>,
+}
+
+/// Which node types an effect applies to
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum UpgradeTarget {
+    All,
+    Node(String),
+}
+
+impl UpgradeTarget {
+    pub fn matches(&self, definition: &NodeDefinition) -> bool {
+        match self {
+            UpgradeTarget::All => true,
+            UpgradeTarget::Node(kind) => *kind == definition.id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum UpgradeEffect {
+    /// Multiplies the sparks of every trigger and click
+    YieldMultiplier { target: UpgradeTarget, factor: f64 },
+    /// Multiplies the sparks of manual clicks only
+    ClickMultiplier { target: UpgradeTarget, factor: f64 },
+    /// Multiplies the seconds between pulses, below 1 pulses faster
+    PulseIntervalMultiplier { target: UpgradeTarget, factor: f32 },
+    /// Widens the trigger pattern by a number of cells
+    TriggerRadius { target: UpgradeTarget, cells: i32 },
+}
+
+/// How sparks were earned, clicks get their own multipliers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum YieldSource {
+    Click,
+    Trigger,
+}
+
+/// The combined effects of the purchased upgrades. Everything the
+/// simulation derives from a [`NodeDefinition`] goes through here.
+#[derive(Debug, Clone, Default)]
+pub struct Modifiers {
+    effects: Vec<UpgradeEffect>,
+}
+
+impl Modifiers {
+    pub fn new(effects: Vec<UpgradeEffect>) -> Self {
+        Self { effects }
+    }
+
+    pub fn yields(
+        &self,
+        definition: &NodeDefinition,
+        source: YieldSource,
+    ) -> SparkAmount {
+        let factor: f64 = self
+            .effects
+            .iter()
+            .map(|effect| match effect {
+                UpgradeEffect::YieldMultiplier { target, factor }
+                    if target.matches(definition) =>
+                {
+                    *factor
+                }
+                UpgradeEffect::ClickMultiplier { target, factor }
+                    if source == YieldSource::Click
+                        && target.matches(definition) =>
+                {
+                    *factor
+                }
+                _ => 1.,
+            })
+            .product();
+
+        definition
+            .yields
+            .checked_mul(SparkAmount::from(factor))
+            .unwrap_or(SparkAmount::MAX)
+    }
+
+    pub fn pulse_interval(&self, definition: &NodeDefinition) -> Option<f32> {
+        let factor: f32 =
+            self.effects
+                .iter()
+                .map(|effect| match effect {
+                    UpgradeEffect::PulseIntervalMultiplier {
+                        target,
+                        factor,
+                    } if target.matches(definition) => *factor,
+                    _ => 1.,
+                })
+                .product();
+
+        definition
+            .pulse_interval
+            .map(|interval| interval * factor)
+            // Zero or negative intervals would pulse forever
+            .filter(|interval| *interval > 0.)
+    }
+
+    /// Extra cells the trigger pattern of a node type reaches
+    pub fn trigger_reach(&self, definition: &NodeDefinition) -> i32 {
+        self.effects
+            .iter()
+            .map(|effect| match effect {
+                UpgradeEffect::TriggerRadius { target, cells }
+                    if target.matches(definition) =>
+                {
+                    *cells
+                }
+                _ => 0,
+            })
+            .sum()
+    }
+}
+
+/// Whether the upgrade list is shown, and which row is highlighted
+#[derive(Resource, Default, PartialEq)]
+pub struct UpgradePanel {
+    pub open: bool,
+    pub selected: usize,
+}
+
+fn add_upgrade_panel(mut cmd: Commands) {
+    cmd.insert_resource(UpgradePanel::default());
+}
+
+fn remove_upgrade_panel(mut cmd: Commands) {
+    cmd.remove_resource::<UpgradePanel>();
+}
+
+const HOTKEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+fn on_keyboard_press(
+    mut panel: ResMut<UpgradePanel>,
+    display_shop: Res<ShowBuildMenu>,
+    node_menu: Res<NodeMenu>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut menu_state: ResMut<NextState<SyltMenuState>>,
+    simulation: Res<SparkSimulation>,
+    mut simulation_actions: EventWriter<SimulationAction>,
+) {
+    if display_shop.0 || *node_menu != NodeMenu::Closed {
+        return;
+    }
+
+    if !panel.open {
+        if keyboard_input.just_pressed(KeyCode::KeyT) {
+            panel.open = true;
+            panel.selected = 0;
+            menu_state.set(SyltMenuState::Disabled);
+        }
+
+        return;
+    }
+
+    if keyboard_input.any_just_pressed([KeyCode::KeyT, KeyCode::Escape]) {
+        panel.open = false;
+        menu_state.set(SyltMenuState::None);
+        return;
+    }
+
+    let available: Vec<String> = simulation
+        .available_upgrades()
+        .map(|upgrade| upgrade.id.clone())
+        .collect();
+
+    if available.is_empty() {
+        return;
+    }
+
+    if keyboard_input.any_just_pressed([
+        KeyCode::KeyW,
+        KeyCode::KeyK,
+        KeyCode::ArrowUp,
+    ]) {
+        panel.selected =
+            (panel.selected + available.len() - 1) % available.len();
+    }
+
+    if keyboard_input.any_just_pressed([
+        KeyCode::KeyS,
+        KeyCode::KeyJ,
+        KeyCode::ArrowDown,
+    ]) {
+        panel.selected = (panel.selected + 1) % available.len();
+    }
+
+    let hotkey = HOTKEYS
+        .iter()
+        .position(|key| keyboard_input.just_pressed(*key))
+        .filter(|index| *index < available.len());
+
+    let confirmed =
+        keyboard_input.any_just_pressed([KeyCode::Space, KeyCode::Enter]);
+
+    let Some(index) =
+        hotkey.or(confirmed.then_some(panel.selected.min(available.len() - 1)))
+    else {
+        return;
+    };
+
+    simulation_actions.write(SimulationAction::BuyUpgrade {
+        id: available[index].clone(),
+    });
+}
+
+#[derive(Component)]
+struct UpgradeList;
+
+/// Index among the available upgrades
+#[derive(Component)]
+struct UpgradeRow(pub usize);
+
+/// Rebuilds the list when it opens or closes and after every purchase
+fn sync_upgrade_panel(
+    mut cmd: Commands,
+    mut shown: Local<bool>,
+    mut simulation_events: EventReader<SimulationEvent>,
+    panel: Res<UpgradePanel>,
+    list_q: Query<Entity, With<UpgradeList>>,
+    simulation: Res<SparkSimulation>,
+    settings: Res<SyltSettings>,
+) {
+    let bought = simulation_events
+        .read()
+        .any(|event| matches!(event, SimulationEvent::UpgradeBought { .. }));
+
+    if *shown == panel.open && !bought {
+        return;
+    }
+
+    *shown = panel.open;
+
+    for list in &list_q {
+        cmd.entity(list).despawn();
+    }
+
+    if !panel.open {
+        return;
+    }
+
+    cmd.spawn((
+        StateScoped(SyltRouterState::Game),
+        UpgradeList,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(SU4 * 4.),
+            right: Val::Px(SU4),
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(SU2),
+            ..default()
+        },
+        Pickable::IGNORE,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            RenderLayers::layer(1),
+            SyltUiText,
+            SyltTextStyle {
+                font_size: 28.,
+                ..default()
+            },
+            SyltText::default(),
+            SyltI18nText::from_key("upgrades"),
+            Node::default(),
+            ContentSize::default(),
+        ));
+
+        for (index, upgrade) in simulation.available_upgrades().enumerate() {
+            parent
+                .spawn((
+                    RenderLayers::layer(1),
+                    UpgradeRow(index),
+                    SyltUiScene::default(),
+                    Node {
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(SU2)),
+                        ..default()
+                    },
+                ))
+                .observe(on_upgrade_row_over)
+                .observe(on_upgrade_row_release)
+                .with_children(|row| {
+                    row.spawn((
+                        RenderLayers::layer(1),
+                        SyltUiText,
+                        SyltTextStyle {
+                            font_size: 20.,
+                            ..default()
+                        },
+                        SyltTextAlign::Left,
+                        SyltText::default(),
+                        SyltI18nText::from_key(&upgrade.i18n_key),
+                        Node::default(),
+                        ContentSize::default(),
+                        Pickable::IGNORE,
+                    ));
+
+                    row.spawn((
+                        RenderLayers::layer(1),
+                        SyltUiText,
+                        SyltTextStyle {
+                            font_size: 16.,
+                            ..default()
+                        },
+                        SyltTextAlign::Left,
+                        SyltText::default(),
+                        SyltI18nText::from_key(&upgrade.description_key),
+                        Node::default(),
+                        ContentSize::default(),
+                        Pickable::IGNORE,
+                    ));
+
+                    row.spawn((
+                        RenderLayers::layer(1),
+                        SyltUiText,
+                        SyltTextStyle {
+                            font_size: 16.,
+                            ..default()
+                        },
+                        SyltTextAlign::Left,
+                        SyltText {
+                            content: format!(
+                                "{}. {} SP",
+                                index + 1,
+                                upgrade.cost.format(settings.number_notation.0)
+                            ),
+                            ..default()
+                        },
+                        Node::default(),
+                        ContentSize::default(),
+                        Pickable::IGNORE,
+                    ));
+                });
+        }
+    });
+}
+
+fn on_upgrade_row_over(
+    trigger: Trigger<Pointer<Over>>,
+    row_q: Query<&UpgradeRow>,
+    panel: Option<ResMut<UpgradePanel>>,
+) {
+    let (Ok(row), Some(mut panel)) = (row_q.get(trigger.target), panel) else {
+        return;
+    };
+
+    if panel.selected != row.0 {
+        panel.selected = row.0;
+    }
+}
+
+fn on_upgrade_row_release(
+    trigger: Trigger<Pointer<Released>>,
+    row_q: Query<&UpgradeRow>,
+    simulation: Option<Res<SparkSimulation>>,
+    mut simulation_actions: EventWriter<SimulationAction>,
+) {
+    if trigger.button != PointerButton::Primary {
+        return;
+    }
+
+    let (Ok(row), Some(simulation)) = (row_q.get(trigger.target), simulation)
+    else {
+        return;
+    };
+
+    let Some(id) = simulation
+        .available_upgrades()
+        .nth(row.0)
+        .map(|upgrade| upgrade.id.clone())
+    else {
+        return;
+    };
+
+    simulation_actions.write(SimulationAction::BuyUpgrade { id });
+}
+
+fn draw_upgrade_rows(
+    row_q: Query<(&UpgradeRow, &ComputedNode, &mut SyltUiScene)>,
+    panel: Res<UpgradePanel>,
+    simulation: Res<SparkSimulation>,
+) {
+    for (row, node, mut scene) in row_q {
+        let affordable = simulation
+            .available_upgrades()
+            .nth(row.0)
+            .is_some_and(|upgrade| simulation.sparks.0 >= upgrade.cost);
+
+        let scene = &mut scene.inner;
+
+        scene.reset();
+
+        let size = node.size();
+        let shape = vello::kurbo::RoundedRect::new(
+            0.,
+            0.,
+            size.x as f64,
+            size.y as f64,
+            4.,
+        );
+
+        // Upgrades the player can not pay for yet are faded out
+        let alpha = if affordable { 0.7 } else { 0.3 };
+
+        scene.fill(
+            vello::peniko::Fill::NonZero,
+            vello::kurbo::Affine::default(),
+            vello::peniko::Color::new([0., 0., 0., alpha]),
+            None,
+            &shape,
+        );
+
+        if row.0 == panel.selected {
+            scene.stroke(
+                &vello::kurbo::Stroke::new(2.),
+                vello::kurbo::Affine::default(),
+                vello::peniko::Color::WHITE,
+                None,
+                &shape,
+            );
+        }
+    }
+}
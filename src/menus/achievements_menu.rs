@@ -0,0 +1,124 @@
+use bevy::{prelude::*, render::view::RenderLayers, ui::ContentSize};
+
+use crate::{
+    canvas::{
+        text::{SyltText, SyltTextAlign, SyltTextStyle},
+        ui_canvas::SyltUiText,
+    },
+    game::achievements::{UnlockedAchievements, ACHIEVEMENTS},
+    i18n::SyltI18nText,
+    routes::SyltRouterState,
+    ui::{
+        cardinal_navigation::{CardinalCrosshairExt, SyltCardinalFocusedEvent},
+        components::button::{SyltButtonExt, SyltButtonNavigationExt},
+        constants::SU4,
+        escape::SyltEscape,
+        layouts::flex_col_center_center,
+    },
+};
+
+use super::SyltMenuState;
+
+pub struct SyltAchievementsMenuPlugin;
+
+impl Plugin for SyltAchievementsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(SyltMenuState::Achievements),
+            spawn_achievements_menu,
+        );
+    }
+}
+
+fn menu_text(font_size: f32) -> impl Bundle {
+    (
+        RenderLayers::layer(1),
+        SyltUiText,
+        SyltTextStyle {
+            font_size,
+            ..default()
+        },
+        SyltTextAlign::Middle,
+        SyltText::default(),
+        Node::default(),
+        ContentSize::default(),
+    )
+}
+
+fn spawn_achievements_menu(
+    mut cmd: Commands,
+    mut event_writer: EventWriter<SyltCardinalFocusedEvent>,
+    unlocked: Res<UnlockedAchievements>,
+) {
+    cmd.spawn_cardinal_crosshair(StateScoped(SyltMenuState::Achievements));
+
+    let unlocked_count = ACHIEVEMENTS
+        .iter()
+        .filter(|achievement| unlocked.contains(achievement.id))
+        .count();
+
+    let wrapper = cmd
+        .spawn((
+            StateScoped(SyltMenuState::Achievements),
+            Name::new("Achievements Container"),
+            flex_col_center_center(),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                menu_text(48.),
+                SyltI18nText::from_key("achievements"),
+            ));
+
+            parent.spawn((
+                menu_text(28.),
+                SyltText {
+                    content: format!(
+                        "{unlocked_count} / {}",
+                        ACHIEVEMENTS.len()
+                    ),
+                    ..default()
+                },
+            ));
+
+            for achievement in ACHIEVEMENTS {
+                let status = if unlocked.contains(achievement.id) {
+                    "unlocked"
+                } else {
+                    "locked"
+                };
+
+                parent
+                    .spawn(Node {
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(SU4 * 2.),
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn((
+                            menu_text(28.),
+                            SyltI18nText::from_key(achievement.i18n_key),
+                        ));
+                        row.spawn((
+                            menu_text(20.),
+                            SyltI18nText::from_key(achievement.description_key),
+                        ));
+                        row.spawn((
+                            menu_text(20.),
+                            SyltI18nText::from_key(status),
+                        ));
+                    });
+            }
+        })
+        .id();
+
+    let back_button = cmd
+        .spawn_sylt_button("back", SyltEscape)
+        .navigate_on_click(SyltRouterState::Title)
+        .id();
+
+    cmd.entity(wrapper).add_child(back_button);
+
+    event_writer.write(SyltCardinalFocusedEvent(Some(back_button)));
+}
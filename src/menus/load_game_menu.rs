@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 
 use crate::{
+    game::save::SyltContinueGameExt,
     routes::SyltRouterState,
     ui::{
         cardinal_navigation::{
@@ -38,6 +39,7 @@ fn spawn_load_game_menu(
 
     let load_button_id = cmd
         .spawn_sylt_button("load", ())
+        .continue_on_click()
         .navigate_on_click(SyltRouterState::Game)
         .id();
 
@@ -1,9 +1,14 @@
 use bevy::prelude::*;
 
+mod achievements_menu;
 mod continue_menu;
 mod load_game_menu;
 mod new_game_menu;
 mod pause_menu;
+mod prestige_menu;
+mod prestige_shop_menu;
+mod scenario_result_menu;
+mod statistics_menu;
 mod title_menu;
 
 #[derive(States, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
@@ -17,6 +22,14 @@ pub enum SyltMenuState {
     NewGame,
     LoadGame,
     Continue,
+    /// Confirming a prestige reset from the pause menu
+    Prestige,
+    PrestigeShop,
+    /// Browsing the statistics from the pause menu
+    Statistics,
+    Achievements,
+    /// Shown once a scenario is won or lost
+    ScenarioResult,
 }
 
 pub struct SyltMenusPlugin;
@@ -31,6 +44,11 @@ impl Plugin for SyltMenusPlugin {
             new_game_menu::SyltNewGameMenuPlugin,
             load_game_menu::LoadGameMenuPlugin,
             continue_menu::SyltContinueMenuPlugin,
+            prestige_menu::SyltPrestigeMenuPlugin,
+            prestige_shop_menu::SyltPrestigeShopMenuPlugin,
+            achievements_menu::SyltAchievementsMenuPlugin,
+            statistics_menu::SyltStatisticsMenuPlugin,
+            scenario_result_menu::SyltScenarioResultMenuPlugin,
         ));
     }
 }
@@ -1,13 +1,25 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, render::view::RenderLayers, ui::ContentSize};
 
 use crate::{
+    canvas::{
+        text::{SyltText, SyltTextAlign, SyltTextStyle},
+        ui_canvas::SyltUiText,
+    },
+    game::{
+        save::GameStart,
+        scenario::{ScenarioRecords, Scenarios},
+    },
+    i18n::SyltI18nText,
     routes::SyltRouterState,
     ui::{
         cardinal_navigation::{
             CardinalCrosshairExt, SyltCardinalFocusedEvent,
             SyltCardinalNavigation,
         },
-        components::button::{SyltButtonExt, SyltButtonNavigationExt},
+        components::button::{
+            SyltButtonExt, SyltButtonNavigationExt, SyltButtonPressed,
+        },
+        constants::SU4,
         escape::SyltEscape,
         layouts::flex_col_center_center,
     },
@@ -23,9 +35,28 @@ impl Plugin for SyltNewGameMenuPlugin {
     }
 }
 
+fn menu_text(font_size: f32) -> impl Bundle {
+    (
+        RenderLayers::layer(1),
+        SyltUiText,
+        SyltTextStyle {
+            font_size,
+            ..default()
+        },
+        SyltTextAlign::Middle,
+        SyltText::default(),
+        Node::default(),
+        ContentSize::default(),
+    )
+}
+
+/// Endless play first, then one entry per scenario with its description and
+/// the fastest win
 fn spawn_new_game_menu(
     mut event_writer: EventWriter<SyltCardinalFocusedEvent>,
     mut cmd: Commands,
+    scenarios: Res<Scenarios>,
+    records: Res<ScenarioRecords>,
 ) {
     cmd.spawn_cardinal_crosshair(StateScoped(SyltMenuState::NewGame));
 
@@ -37,29 +68,85 @@ fn spawn_new_game_menu(
         .id();
 
     let start_button_id = cmd
-        .spawn_sylt_button("start", ())
+        .spawn_sylt_button("endless", ())
         .navigate_on_click(SyltRouterState::Game)
         .id();
 
+    cmd.entity(wrapper).add_child(start_button_id);
+
+    let scenarios_title = cmd
+        .spawn((menu_text(48.), SyltI18nText::from_key("scenarios")))
+        .id();
+
+    cmd.entity(wrapper).add_child(scenarios_title);
+
+    let mut button_ids = vec![start_button_id];
+
+    for scenario in &scenarios.0 {
+        let id = scenario.id.clone();
+
+        let button_id = cmd
+            .spawn_sylt_button(&scenario.i18n_key, ())
+            .observe(
+                move |_: Trigger<SyltButtonPressed>,
+                      mut game_start: ResMut<GameStart>| {
+                    *game_start = GameStart::Scenario(id.clone());
+                },
+            )
+            .navigate_on_click(SyltRouterState::Game)
+            .id();
+
+        let details_id = cmd
+            .spawn(Node {
+                display: Display::Flex,
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(SU4 * 2.),
+                ..default()
+            })
+            .with_children(|row| {
+                row.spawn((
+                    menu_text(20.),
+                    SyltI18nText::from_key(&scenario.description_key),
+                ));
+
+                if let Some(best) = records.best(&scenario.id) {
+                    row.spawn((
+                        menu_text(20.),
+                        SyltI18nText::from_key("best time"),
+                    ));
+                    row.spawn((
+                        menu_text(20.),
+                        SyltText {
+                            content: format!("{best:.1}s"),
+                            ..default()
+                        },
+                    ));
+                }
+            })
+            .id();
+
+        cmd.entity(wrapper).add_children(&[button_id, details_id]);
+        button_ids.push(button_id);
+    }
+
     let back_button_id = cmd
         .spawn_sylt_button("back", SyltEscape)
         .navigate_on_click(SyltRouterState::Title)
         .id();
 
-    cmd.entity(start_button_id).insert(SyltCardinalNavigation {
-        north: Some(back_button_id),
-        south: Some(back_button_id),
-        ..Default::default()
-    });
+    cmd.entity(wrapper).add_child(back_button_id);
+    button_ids.push(back_button_id);
 
-    cmd.entity(back_button_id).insert(SyltCardinalNavigation {
-        north: Some(start_button_id),
-        south: Some(start_button_id),
-        ..Default::default()
-    });
+    for (index, button_id) in button_ids.iter().enumerate() {
+        let count = button_ids.len();
 
-    cmd.entity(wrapper)
-        .add_children(&[start_button_id, back_button_id]);
+        cmd.entity(*button_id).insert(SyltCardinalNavigation {
+            north: Some(button_ids[(index + count - 1) % count]),
+            south: Some(button_ids[(index + 1) % count]),
+            ..Default::default()
+        });
+    }
 
     event_writer.write(SyltCardinalFocusedEvent(Some(start_button_id)));
 }
@@ -53,17 +53,29 @@ fn spawn_pause_menu(
                 menu_state.set(SyltMenuState::None);
             },
         )
+        .id();
+
+    event_writer.write(SyltCardinalFocusedEvent(Some(resume_button)));
+
+    let prestige_button = cmd
+        .spawn_sylt_button("prestige", ())
         .observe(
-            |_trigger: Trigger<Pointer<Released>>,
-             mut menu_state: ResMut<NextState<SyltMenuState>>,
-             mut pause_state: ResMut<NextState<SyltGamePauseState>>| {
-                pause_state.set(SyltGamePauseState(false));
-                menu_state.set(SyltMenuState::None);
+            |_trigger: Trigger<SyltButtonPressed>,
+             mut menu_state: ResMut<NextState<SyltMenuState>>| {
+                menu_state.set(SyltMenuState::Prestige);
             },
         )
         .id();
 
-    event_writer.write(SyltCardinalFocusedEvent(Some(resume_button)));
+    let statistics_button = cmd
+        .spawn_sylt_button("statistics", ())
+        .observe(
+            |_trigger: Trigger<SyltButtonPressed>,
+             mut menu_state: ResMut<NextState<SyltMenuState>>| {
+                menu_state.set(SyltMenuState::Statistics);
+            },
+        )
+        .id();
 
     let main_menu_button = cmd
         .spawn_sylt_button("title_menu", ())
@@ -72,16 +84,33 @@ fn spawn_pause_menu(
 
     cmd.entity(resume_button).insert(SyltCardinalNavigation {
         north: Some(main_menu_button),
-        south: Some(main_menu_button),
+        south: Some(prestige_button),
         ..Default::default()
     });
 
-    cmd.entity(main_menu_button).insert(SyltCardinalNavigation {
+    cmd.entity(prestige_button).insert(SyltCardinalNavigation {
         north: Some(resume_button),
+        south: Some(statistics_button),
+        ..Default::default()
+    });
+
+    cmd.entity(statistics_button)
+        .insert(SyltCardinalNavigation {
+            north: Some(prestige_button),
+            south: Some(main_menu_button),
+            ..Default::default()
+        });
+
+    cmd.entity(main_menu_button).insert(SyltCardinalNavigation {
+        north: Some(statistics_button),
         south: Some(resume_button),
         ..Default::default()
     });
 
-    cmd.entity(container_id)
-        .add_children(&[resume_button, main_menu_button]);
+    cmd.entity(container_id).add_children(&[
+        resume_button,
+        prestige_button,
+        statistics_button,
+        main_menu_button,
+    ]);
 }
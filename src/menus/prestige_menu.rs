@@ -0,0 +1,138 @@
+use bevy::{prelude::*, render::view::RenderLayers, ui::ContentSize};
+
+use crate::{
+    canvas::{
+        text::{SyltText, SyltTextAlign, SyltTextStyle},
+        ui_canvas::SyltUiText,
+    },
+    game::{
+        prestige::{embers_for, PrestigeEvent},
+        simulation::SparkSimulation,
+    },
+    i18n::SyltI18nText,
+    menus::SyltMenuState,
+    ui::{
+        cardinal_navigation::{
+            SyltCardinalFocusedEvent, SyltCardinalNavigation,
+        },
+        components::button::{SyltButtonExt, SyltButtonPressed},
+    },
+};
+
+pub struct SyltPrestigeMenuPlugin;
+
+impl Plugin for SyltPrestigeMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(SyltMenuState::Prestige), spawn_prestige_menu);
+    }
+}
+
+fn spawn_prestige_menu(
+    mut cmd: Commands,
+    mut event_writer: EventWriter<SyltCardinalFocusedEvent>,
+    simulation: Option<Res<SparkSimulation>>,
+) {
+    let embers = simulation
+        .map(|simulation| embers_for(simulation.lifetime_sparks))
+        .unwrap_or_default();
+
+    let container_id = cmd
+        .spawn((
+            StateScoped(SyltMenuState::Prestige),
+            Name::new("Prestige Menu Container"),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(10.0),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                RenderLayers::layer(1),
+                SyltUiText,
+                SyltTextStyle {
+                    font_size: 28.,
+                    ..default()
+                },
+                SyltTextAlign::Middle,
+                SyltText::default(),
+                SyltI18nText::from_key("prestige warning"),
+                Node::default(),
+                ContentSize::default(),
+            ));
+
+            parent.spawn((
+                RenderLayers::layer(1),
+                SyltUiText,
+                SyltTextStyle {
+                    font_size: 28.,
+                    ..default()
+                },
+                SyltTextAlign::Middle,
+                SyltText::default(),
+                SyltI18nText::from_key("embers gained"),
+                Node::default(),
+                ContentSize::default(),
+            ));
+
+            parent.spawn((
+                RenderLayers::layer(1),
+                SyltUiText,
+                SyltTextStyle {
+                    font_size: 48.,
+                    ..default()
+                },
+                SyltTextAlign::Middle,
+                SyltText {
+                    content: format!("+{embers}"),
+                    ..default()
+                },
+                Node::default(),
+                ContentSize::default(),
+            ));
+        })
+        .id();
+
+    let confirm_button = cmd
+        .spawn_sylt_button("prestige confirm", ())
+        .observe(
+            |_trigger: Trigger<SyltButtonPressed>,
+             mut prestige_writer: EventWriter<PrestigeEvent>| {
+                prestige_writer.write(PrestigeEvent::Reset);
+            },
+        )
+        .id();
+
+    let back_button = cmd
+        .spawn_sylt_button("back", ())
+        .observe(
+            |_trigger: Trigger<SyltButtonPressed>,
+             mut menu_state: ResMut<NextState<SyltMenuState>>| {
+                menu_state.set(SyltMenuState::Pause);
+            },
+        )
+        .id();
+
+    // Resetting by accident should take more than a single key press
+    event_writer.write(SyltCardinalFocusedEvent(Some(back_button)));
+
+    cmd.entity(confirm_button).insert(SyltCardinalNavigation {
+        north: Some(back_button),
+        south: Some(back_button),
+        ..Default::default()
+    });
+
+    cmd.entity(back_button).insert(SyltCardinalNavigation {
+        north: Some(confirm_button),
+        south: Some(confirm_button),
+        ..Default::default()
+    });
+
+    cmd.entity(container_id)
+        .add_children(&[confirm_button, back_button]);
+}
@@ -0,0 +1,208 @@
+use bevy::{prelude::*, render::view::RenderLayers, ui::ContentSize};
+
+use crate::{
+    canvas::{
+        text::{SyltText, SyltTextAlign, SyltTextStyle},
+        ui_canvas::SyltUiText,
+    },
+    game::{
+        catalog::NodeCatalog,
+        prestige::{PrestigeEvent, PrestigeProgress},
+        save::SyltContinueGameExt,
+    },
+    i18n::SyltI18nText,
+    routes::SyltRouterState,
+    ui::{
+        cardinal_navigation::{
+            CardinalCrosshairExt, SyltCardinalFocusedEvent,
+            SyltCardinalNavigation,
+        },
+        components::button::{
+            SyltButtonExt, SyltButtonNavigationExt, SyltButtonPressed,
+        },
+        layouts::flex_col_center_center,
+    },
+};
+
+use super::SyltMenuState;
+
+pub struct SyltPrestigeShopMenuPlugin;
+
+impl Plugin for SyltPrestigeShopMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(SyltMenuState::PrestigeShop),
+            spawn_prestige_shop_menu,
+        );
+
+        app.add_systems(
+            Update,
+            (update_ember_count, update_bonus_status).run_if(
+                in_state(SyltMenuState::PrestigeShop)
+                    .and(resource_changed::<PrestigeProgress>),
+            ),
+        );
+    }
+}
+
+/// Shows the embers left to spend
+#[derive(Component)]
+struct EmberCount;
+
+/// Shows the price of a bonus, or that it is already bought
+#[derive(Component)]
+struct BonusStatus(String);
+
+fn menu_text(font_size: f32) -> impl Bundle {
+    (
+        RenderLayers::layer(1),
+        SyltUiText,
+        SyltTextStyle {
+            font_size,
+            ..default()
+        },
+        SyltTextAlign::Middle,
+        Node::default(),
+        ContentSize::default(),
+    )
+}
+
+fn bonus_status(
+    progress: &PrestigeProgress,
+    id: &str,
+    cost: u64,
+) -> Option<String> {
+    if progress.bonuses.iter().any(|bonus| bonus == id) {
+        None
+    } else {
+        Some(cost.to_string())
+    }
+}
+
+fn spawn_prestige_shop_menu(
+    mut cmd: Commands,
+    mut event_writer: EventWriter<SyltCardinalFocusedEvent>,
+    catalog: Res<NodeCatalog>,
+    progress: Res<PrestigeProgress>,
+) {
+    cmd.spawn_cardinal_crosshair(StateScoped(SyltMenuState::PrestigeShop));
+
+    let wrapper = cmd
+        .spawn((
+            StateScoped(SyltMenuState::PrestigeShop),
+            Name::new("Prestige Shop Container"),
+            flex_col_center_center(),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                menu_text(28.),
+                SyltText::default(),
+                SyltI18nText::from_key("embers"),
+            ));
+
+            parent.spawn((
+                EmberCount,
+                menu_text(48.),
+                SyltText {
+                    content: progress.embers.to_string(),
+                    ..default()
+                },
+            ));
+        })
+        .id();
+
+    let mut buttons = Vec::new();
+
+    for bonus in catalog.prestige_bonuses.iter() {
+        let id = bonus.id.clone();
+        let button = cmd
+            .spawn_sylt_button(&bonus.i18n_key, ())
+            .observe({
+                let id = id.clone();
+                move |_trigger: Trigger<SyltButtonPressed>,
+                      mut prestige_writer: EventWriter<PrestigeEvent>| {
+                    prestige_writer.write(PrestigeEvent::BuyBonus(id.clone()));
+                }
+            })
+            .id();
+
+        let description = cmd
+            .spawn((
+                menu_text(20.),
+                SyltText::default(),
+                SyltI18nText::from_key(&bonus.description_key),
+            ))
+            .id();
+
+        let mut status = cmd.spawn((BonusStatus(id.clone()), menu_text(20.)));
+        match bonus_status(&progress, &id, bonus.cost) {
+            Some(content) => {
+                status.insert(SyltText {
+                    content,
+                    ..default()
+                });
+            }
+            None => {
+                status.insert((
+                    SyltText::default(),
+                    SyltI18nText::from_key("bought"),
+                ));
+            }
+        }
+        let status = status.id();
+
+        cmd.entity(wrapper)
+            .add_children(&[button, description, status]);
+        buttons.push(button);
+    }
+
+    let ready_button = cmd
+        .spawn_sylt_button("ready", ())
+        .continue_on_click()
+        .navigate_on_click(SyltRouterState::Game)
+        .id();
+    cmd.entity(wrapper).add_child(ready_button);
+    buttons.push(ready_button);
+
+    for (index, button) in buttons.iter().enumerate() {
+        let north = buttons[(index + buttons.len() - 1) % buttons.len()];
+        let south = buttons[(index + 1) % buttons.len()];
+        cmd.entity(*button).insert(SyltCardinalNavigation {
+            north: Some(north),
+            south: Some(south),
+            ..Default::default()
+        });
+    }
+
+    event_writer.write(SyltCardinalFocusedEvent(Some(ready_button)));
+}
+
+fn update_ember_count(
+    progress: Res<PrestigeProgress>,
+    mut count_q: Query<&mut SyltText, With<EmberCount>>,
+) {
+    for mut text in count_q.iter_mut() {
+        text.content = progress.embers.to_string();
+    }
+}
+
+fn update_bonus_status(
+    mut cmd: Commands,
+    progress: Res<PrestigeProgress>,
+    catalog: Res<NodeCatalog>,
+    status_q: Query<(Entity, &BonusStatus), Without<SyltI18nText>>,
+) {
+    for (entity, status) in status_q.iter() {
+        let Some(bonus) = catalog
+            .prestige_bonuses
+            .iter()
+            .find(|bonus| bonus.id == status.0)
+        else {
+            continue;
+        };
+
+        if bonus_status(&progress, &bonus.id, bonus.cost).is_none() {
+            cmd.entity(entity).insert(SyltI18nText::from_key("bought"));
+        }
+    }
+}
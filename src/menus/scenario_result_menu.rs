@@ -0,0 +1,130 @@
+use bevy::{prelude::*, render::view::RenderLayers, ui::ContentSize};
+
+use crate::{
+    canvas::{
+        text::{SyltText, SyltTextAlign, SyltTextStyle},
+        ui_canvas::SyltUiText,
+    },
+    game::scenario::{ActiveScenario, ScenarioOutcome, ScenarioRecords},
+    i18n::SyltI18nText,
+    routes::SyltRouterState,
+    ui::{
+        cardinal_navigation::{
+            SyltCardinalFocusedEvent, SyltCardinalNavigation,
+        },
+        components::button::{SyltButtonExt, SyltButtonNavigationExt},
+        layouts::flex_col_center_center,
+    },
+};
+
+use super::SyltMenuState;
+
+pub struct SyltScenarioResultMenuPlugin;
+
+impl Plugin for SyltScenarioResultMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(SyltMenuState::ScenarioResult),
+            spawn_scenario_result_menu,
+        );
+    }
+}
+
+fn menu_text(font_size: f32) -> impl Bundle {
+    (
+        RenderLayers::layer(1),
+        SyltUiText,
+        SyltTextStyle {
+            font_size,
+            ..default()
+        },
+        SyltTextAlign::Middle,
+        SyltText::default(),
+        Node::default(),
+        ContentSize::default(),
+    )
+}
+
+fn spawn_scenario_result_menu(
+    mut cmd: Commands,
+    mut event_writer: EventWriter<SyltCardinalFocusedEvent>,
+    active: Option<Res<ActiveScenario>>,
+    records: Res<ScenarioRecords>,
+) {
+    let Some(active) = active else {
+        return;
+    };
+
+    let outcome_key = match active.outcome {
+        Some(ScenarioOutcome::Won) => "scenario won",
+        _ => "scenario lost",
+    };
+    let best = records.best(&active.id);
+
+    let wrapper = cmd
+        .spawn((
+            StateScoped(SyltMenuState::ScenarioResult),
+            Name::new("Scenario Result Container"),
+            flex_col_center_center(),
+        ))
+        .with_children(|parent| {
+            parent.spawn((menu_text(48.), SyltI18nText::from_key(outcome_key)));
+
+            for (key, seconds) in
+                [("time", Some(active.elapsed)), ("best time", best)]
+            {
+                let Some(seconds) = seconds else {
+                    continue;
+                };
+
+                parent
+                    .spawn(Node {
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(10.),
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn((
+                            menu_text(28.),
+                            SyltI18nText::from_key(key),
+                        ));
+                        row.spawn((
+                            menu_text(28.),
+                            SyltText {
+                                content: format!("{seconds:.1}s"),
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+        })
+        .id();
+
+    let scenarios_button = cmd
+        .spawn_sylt_button("scenarios", ())
+        .navigate_on_click(SyltRouterState::NewGame)
+        .id();
+
+    let title_button = cmd
+        .spawn_sylt_button("title_menu", ())
+        .navigate_on_click(SyltRouterState::Title)
+        .id();
+
+    cmd.entity(scenarios_button).insert(SyltCardinalNavigation {
+        north: Some(title_button),
+        south: Some(title_button),
+        ..Default::default()
+    });
+
+    cmd.entity(title_button).insert(SyltCardinalNavigation {
+        north: Some(scenarios_button),
+        south: Some(scenarios_button),
+        ..Default::default()
+    });
+
+    cmd.entity(wrapper)
+        .add_children(&[scenarios_button, title_button]);
+
+    event_writer.write(SyltCardinalFocusedEvent(Some(scenarios_button)));
+}
@@ -0,0 +1,319 @@
+use bevy::{prelude::*, render::view::RenderLayers, ui::ContentSize};
+
+use crate::{
+    canvas::{
+        text::{SyltText, SyltTextAlign, SyltTextStyle},
+        ui_canvas::SyltUiText,
+    },
+    game::{
+        catalog::NodeCatalog,
+        currency::SparkAmount,
+        statistics::{GameStatistics, Statistics},
+    },
+    i18n::SyltI18nText,
+    menus::SyltMenuState,
+    settings::SyltSettings,
+    ui::{
+        cardinal_navigation::{
+            SyltCardinalFocusedEvent, SyltCardinalNavigation,
+        },
+        components::button::{SyltButtonExt, SyltButtonPressed},
+        layouts::{horizontal_center_layout, label_layout},
+    },
+};
+
+pub struct SyltStatisticsMenuPlugin;
+
+impl Plugin for SyltStatisticsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StatisticsView>();
+
+        app.add_systems(
+            OnEnter(SyltMenuState::Statistics),
+            spawn_statistics_menu,
+        );
+
+        app.add_systems(
+            Update,
+            (update_statistic_values).run_if(
+                in_state(SyltMenuState::Statistics).and(
+                    resource_changed::<StatisticsView>
+                        .or(resource_changed::<GameStatistics>),
+                ),
+            ),
+        );
+    }
+}
+
+/// Which statistics the page shows
+#[derive(Resource, Default, Clone, Copy, PartialEq)]
+enum StatisticsView {
+    #[default]
+    Session,
+    Game,
+    Lifetime,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum StatisticKind {
+    Clicks,
+    Triggers,
+    /// Sparks earned by every node type together
+    Earned,
+    /// Sparks earned by one node type
+    EarnedBy(String),
+    Spent,
+    Built,
+    Sold,
+    LongestChain,
+    PlayTime,
+}
+
+impl StatisticKind {
+    fn i18n_key(&self) -> &str {
+        match self {
+            Self::Clicks => "statistics clicks",
+            Self::Triggers => "statistics triggers",
+            Self::Earned => "statistics earned",
+            // Labelled with the node name instead
+            Self::EarnedBy(_) => "",
+            Self::Spent => "statistics spent",
+            Self::Built => "statistics built",
+            Self::Sold => "statistics sold",
+            Self::LongestChain => "statistics longest chain",
+            Self::PlayTime => "statistics play time",
+        }
+    }
+
+    fn value(
+        &self,
+        statistics: &Statistics,
+        settings: &SyltSettings,
+    ) -> String {
+        let notation = settings.number_notation.0;
+
+        match self {
+            Self::Clicks => statistics.clicks.to_string(),
+            Self::Triggers => statistics.triggers.to_string(),
+            Self::Earned => statistics
+                .earned
+                .values()
+                .fold(SparkAmount::ZERO, |mut total, amount| {
+                    total += *amount;
+                    total
+                })
+                .format(notation),
+            Self::EarnedBy(kind) => statistics
+                .earned
+                .get(kind)
+                .copied()
+                .unwrap_or_default()
+                .format(notation),
+            Self::Spent => statistics.spent.format(notation),
+            Self::Built => statistics.built.to_string(),
+            Self::Sold => statistics.sold.to_string(),
+            Self::LongestChain => statistics.longest_chain.to_string(),
+            Self::PlayTime => format_play_time(statistics.play_time),
+        }
+    }
+}
+
+fn format_play_time(seconds: f64) -> String {
+    let seconds = seconds.max(0.) as u64;
+
+    format!(
+        "{}h {:02}m {:02}s",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+#[derive(Component)]
+struct StatisticValue(StatisticKind);
+
+fn statistics_text(font_size: f32) -> impl Bundle {
+    (
+        RenderLayers::layer(1),
+        SyltUiText,
+        SyltTextStyle {
+            font_size,
+            ..default()
+        },
+        SyltText::default(),
+        Node::default(),
+        ContentSize::default(),
+    )
+}
+
+fn view_button(
+    cmd: &mut Commands,
+    i18n_key: &str,
+    view: StatisticsView,
+) -> Entity {
+    cmd.spawn_sylt_button(i18n_key, ())
+        .observe(
+            move |_trigger: Trigger<SyltButtonPressed>,
+                  mut statistics_view: ResMut<StatisticsView>| {
+                *statistics_view = view;
+            },
+        )
+        .id()
+}
+
+fn spawn_statistics_menu(
+    mut cmd: Commands,
+    mut event_writer: EventWriter<SyltCardinalFocusedEvent>,
+    mut statistics_view: ResMut<StatisticsView>,
+    catalog: Res<NodeCatalog>,
+) {
+    // Marks the view as changed so the values are filled in
+    *statistics_view = StatisticsView::Session;
+
+    let mut kinds = vec![
+        StatisticKind::PlayTime,
+        StatisticKind::Clicks,
+        StatisticKind::Triggers,
+        StatisticKind::LongestChain,
+        StatisticKind::Earned,
+    ];
+    kinds.extend(
+        catalog
+            .nodes
+            .iter()
+            .map(|definition| StatisticKind::EarnedBy(definition.id.clone())),
+    );
+    kinds.extend([
+        StatisticKind::Spent,
+        StatisticKind::Built,
+        StatisticKind::Sold,
+    ]);
+
+    let container_id = cmd
+        .spawn((
+            StateScoped(SyltMenuState::Statistics),
+            Name::new("Statistics Menu Container"),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(10.0),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                statistics_text(48.),
+                SyltTextAlign::Middle,
+                SyltI18nText::from_key("statistics"),
+            ));
+        })
+        .id();
+
+    let view_row = cmd.spawn(horizontal_center_layout()).id();
+    let session_button =
+        view_button(&mut cmd, "session", StatisticsView::Session);
+    let game_button = view_button(&mut cmd, "this game", StatisticsView::Game);
+    let lifetime_button =
+        view_button(&mut cmd, "lifetime", StatisticsView::Lifetime);
+    cmd.entity(view_row).add_children(&[
+        session_button,
+        game_button,
+        lifetime_button,
+    ]);
+
+    let rows = cmd
+        .spawn(Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            min_width: Val::Px(480.),
+            ..default()
+        })
+        .with_children(|parent| {
+            for kind in kinds {
+                let label = match &kind {
+                    StatisticKind::EarnedBy(id) => catalog
+                        .nodes
+                        .iter()
+                        .find(|definition| definition.id == *id)
+                        .map(|definition| definition.i18n_key.as_str())
+                        .unwrap_or_default(),
+                    kind => kind.i18n_key(),
+                };
+
+                parent.spawn(label_layout()).with_children(|row| {
+                    row.spawn((
+                        statistics_text(24.),
+                        SyltI18nText::from_key(label),
+                    ));
+                    row.spawn((
+                        statistics_text(24.),
+                        StatisticValue(kind.clone()),
+                    ));
+                });
+            }
+        })
+        .id();
+
+    let back_button = cmd
+        .spawn_sylt_button("back", ())
+        .observe(
+            |_trigger: Trigger<SyltButtonPressed>,
+             mut menu_state: ResMut<NextState<SyltMenuState>>| {
+                menu_state.set(SyltMenuState::Pause);
+            },
+        )
+        .id();
+
+    cmd.entity(session_button).insert(SyltCardinalNavigation {
+        east: Some(game_button),
+        west: Some(lifetime_button),
+        north: Some(back_button),
+        south: Some(back_button),
+    });
+
+    cmd.entity(game_button).insert(SyltCardinalNavigation {
+        east: Some(lifetime_button),
+        west: Some(session_button),
+        north: Some(back_button),
+        south: Some(back_button),
+    });
+
+    cmd.entity(lifetime_button).insert(SyltCardinalNavigation {
+        east: Some(session_button),
+        west: Some(game_button),
+        north: Some(back_button),
+        south: Some(back_button),
+    });
+
+    cmd.entity(back_button).insert(SyltCardinalNavigation {
+        north: Some(session_button),
+        south: Some(session_button),
+        ..Default::default()
+    });
+
+    cmd.entity(container_id)
+        .add_children(&[view_row, rows, back_button]);
+
+    event_writer.write(SyltCardinalFocusedEvent(Some(session_button)));
+}
+
+fn update_statistic_values(
+    statistics_view: Res<StatisticsView>,
+    statistics: Res<GameStatistics>,
+    settings: Res<SyltSettings>,
+    mut value_q: Query<(&mut SyltText, &StatisticValue)>,
+) {
+    let statistics = match *statistics_view {
+        StatisticsView::Session => &statistics.session,
+        StatisticsView::Game => &statistics.game,
+        StatisticsView::Lifetime => &statistics.lifetime,
+    };
+
+    for (mut text, value) in value_q.iter_mut() {
+        text.content = value.0.value(statistics, &settings);
+    }
+}
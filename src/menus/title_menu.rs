@@ -6,6 +6,7 @@ use crate::{
         text::{SyltText, SyltTextStyle},
         ui_canvas::SyltUiText,
     },
+    game::save::{SavedGame, SyltContinueGameExt},
     routes::SyltRouterState,
     ui::{
         cardinal_navigation::{
@@ -69,6 +70,7 @@ fn spawn_title_menu(
     mut cmd: Commands,
     mut event_writer: EventWriter<SyltCardinalFocusedEvent>,
     last_left_menu_item_res: Res<LastLeftMenuItem>,
+    saved_game: Res<SavedGame>,
 ) {
     cmd.spawn_cardinal_crosshair(StateScoped(SyltRouterState::Title));
 
@@ -140,17 +142,6 @@ fn spawn_title_menu(
     let youtube_button = cmd
         .spawn_sylt_button("youtube", RightItem)
         .observe(last_right_menu_item_observer::<SyltButtonFocused>())
-        .observe(move |_: Trigger<Pointer<Released>>| {
-            let youtube_url = dotenv!("YOUTUBE_URL").to_string();
-
-            #[cfg(not(target_arch = "wasm32"))]
-            open::that(youtube_url).unwrap();
-
-            #[cfg(target_arch = "wasm32")]
-            if let Some(window) = web_sys::window() {
-                window.open_with_url_and_target(&youtube_url, "_blank");
-            }
-        })
         .observe(move |_: Trigger<SyltButtonPressed>| {
             let youtube_url = dotenv!("YOUTUBE_URL").to_string();
 
@@ -206,16 +197,17 @@ fn spawn_title_menu(
         },))
         .id();
 
-    // TODO: Disable if no save games
-    // let continue_button = cmd
-    //     .spawn_sylt_button("continue", LeftItem)
-    //     .navigate_on_click(SyltRouterState::Continue)
-    //     .observe(last_left_menu_item_observer::<SyltButtonFocused>())
-    //     .id();
+    let continue_button = saved_game.0.is_some().then(|| {
+        cmd.spawn_sylt_button("continue", LeftItem)
+            .continue_on_click()
+            .navigate_on_click(SyltRouterState::Game)
+            .observe(last_left_menu_item_observer::<SyltButtonFocused>())
+            .id()
+    });
 
     let new_game_button = cmd
         .spawn_sylt_button("new game", LeftItem)
-        .navigate_on_click(SyltRouterState::Game)
+        .navigate_on_click(SyltRouterState::NewGame)
         .observe(last_left_menu_item_observer::<SyltButtonFocused>())
         .id();
 
@@ -231,6 +223,12 @@ fn spawn_title_menu(
     //     .observe(last_left_menu_item_observer::<SyltButtonFocused>())
     //     .id();
 
+    let achievements_button = cmd
+        .spawn_sylt_button("achievements", LeftItem)
+        .navigate_on_click(SyltRouterState::Achievements)
+        .observe(last_left_menu_item_observer::<SyltButtonFocused>())
+        .id();
+
     let settings_button = cmd
         .spawn_sylt_button("settings", LeftItem)
         .navigate_on_click(SyltRouterState::Settings)
@@ -243,16 +241,18 @@ fn spawn_title_menu(
     //     .observe(last_left_menu_item_observer::<SyltButtonFocused>())
     //     .id();
 
-    // cmd.entity(continue_button).insert(SyltCardinalNavigation {
-    //     north: Some(exit_button),
-    //     south: Some(new_game_button),
-    //     east: Some(youtube_button),
-    //     ..default()
-    // });
+    if let Some(continue_button) = continue_button {
+        cmd.entity(continue_button).insert(SyltCardinalNavigation {
+            north: Some(settings_button),
+            south: Some(new_game_button),
+            east: Some(youtube_button),
+            ..default()
+        });
+    }
 
     cmd.entity(new_game_button).insert(SyltCardinalNavigation {
-        north: Some(settings_button),
-        south: Some(settings_button),
+        north: Some(continue_button.unwrap_or(settings_button)),
+        south: Some(achievements_button),
         east: Some(youtube_button),
         ..default()
     });
@@ -272,9 +272,17 @@ fn spawn_title_menu(
     //         ..default()
     //     });
 
+    cmd.entity(achievements_button)
+        .insert(SyltCardinalNavigation {
+            north: Some(new_game_button),
+            south: Some(settings_button),
+            east: Some(youtube_button),
+            ..default()
+        });
+
     cmd.entity(settings_button).insert(SyltCardinalNavigation {
-        north: Some(new_game_button),
-        south: Some(new_game_button),
+        north: Some(achievements_button),
+        south: Some(continue_button.unwrap_or(new_game_button)),
         east: Some(youtube_button),
         ..default()
     });
@@ -292,7 +300,12 @@ fn spawn_title_menu(
     cmd.entity(youtube_button).insert(SyltCardinalNavigation {
         // north: Some(discord_button),
         // south: Some(discord_button),
-        west: Some(last_left_menu_item_res.0.unwrap_or(new_game_button)),
+        west: Some(
+            last_left_menu_item_res
+                .0
+                .or(continue_button)
+                .unwrap_or(new_game_button),
+        ),
         ..default()
     });
 
@@ -303,9 +316,13 @@ fn spawn_title_menu(
     //     ..default()
     // });
 
+    if let Some(continue_button) = continue_button {
+        cmd.entity(menu_container).add_child(continue_button);
+    }
+
     let menu_container_id = cmd
         .entity(menu_container)
-        .add_children(&[new_game_button, settings_button])
+        .add_children(&[new_game_button, achievements_button, settings_button])
         .id();
 
     let external_id = cmd
@@ -316,6 +333,7 @@ fn spawn_title_menu(
     cmd.entity(wrapper)
         .add_children(&[menu_container_id, external_id]);
 
-    // TODO: Focus continue button if save games exist
-    event_writer.write(SyltCardinalFocusedEvent(Some(new_game_button)));
+    event_writer.write(SyltCardinalFocusedEvent(Some(
+        continue_button.unwrap_or(new_game_button),
+    )));
 }
@@ -0,0 +1,26 @@
+use bevy::prelude::*;
+
+use crate::{menus::SyltMenuState, routes::SyltRouterState};
+
+pub struct SyltAchievementsRoutePlugin;
+
+impl Plugin for SyltAchievementsRoutePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(SyltRouterState::Achievements),
+            open_achievements_menu,
+        );
+        app.add_systems(
+            OnExit(SyltRouterState::Achievements),
+            close_achievements_menu,
+        );
+    }
+}
+
+fn open_achievements_menu(mut menu_state: ResMut<NextState<SyltMenuState>>) {
+    menu_state.set(SyltMenuState::Achievements);
+}
+
+fn close_achievements_menu(mut menu_state: ResMut<NextState<SyltMenuState>>) {
+    menu_state.set(SyltMenuState::None);
+}
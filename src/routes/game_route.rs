@@ -26,6 +26,7 @@ impl Plugin for SyltGameRoutePlugin {
             close_pause_menu.run_if(
                 in_state(SyltRouterState::Game)
                     .and(not(in_state(SyltMenuState::None)))
+                    .and(not(in_state(SyltMenuState::ScenarioResult)))
                     .and(
                         input_just_pressed(KeyCode::KeyP)
                             .or(input_just_pressed(KeyCode::Escape)),
@@ -1,6 +1,9 @@
 use bevy::prelude::*;
 
-use crate::{routes::SyltRouterState, sounds::SyltSoundAssets};
+use crate::{
+    game::catalog::NodeCatalogAssets, routes::SyltRouterState,
+    sounds::SyltSoundAssets,
+};
 
 pub struct SyltLoadingRoutePlugin;
 
@@ -18,6 +21,7 @@ fn assets_loaded_system(
     // svg_handles: Res<SyltSvgCollection>,
     asset_server: Res<AssetServer>,
     sound_assets: Res<SyltSoundAssets>,
+    node_catalog_assets: Res<NodeCatalogAssets>,
 ) {
     if !asset_server.is_loaded(&sound_assets.noop) {
         return;
@@ -27,5 +31,9 @@ fn assets_loaded_system(
         return;
     }
 
+    if !asset_server.is_loaded(&node_catalog_assets.catalog) {
+        return;
+    }
+
     state.set(SyltRouterState::Splash);
 }
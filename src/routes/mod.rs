@@ -1,8 +1,11 @@
 use bevy::prelude::*;
 
+mod achievements_route;
 mod exit_route;
 pub mod game_route;
 mod loading_route;
+mod new_game_route;
+mod prestige_route;
 mod settings_route;
 mod splash_route;
 mod title_route;
@@ -32,9 +35,13 @@ pub enum SyltRouterState {
     // - Profile
     // - Create Account
     Game,
+    /// Spending embers after a prestige reset
+    Prestige,
     // - New
     // - Identities
     Settings,
+    /// Browsing the unlocked achievements
+    Achievements,
     // - Sound
     // - Gameplay
     // - Video
@@ -59,9 +66,12 @@ impl Plugin for SyltRoutesPlugin {
         app.init_state::<SyltRouterState>();
 
         app.add_plugins((
+            achievements_route::SyltAchievementsRoutePlugin,
             exit_route::SyltExitRoutePlugin,
             game_route::SyltGameRoutePlugin,
             loading_route::SyltLoadingRoutePlugin,
+            new_game_route::SyltNewGameRoutePlugin,
+            prestige_route::SyltPrestigeRoutePlugin,
             settings_route::SyltSettingsRoutePlugin,
             splash_route::SyltSplashRoutePlugin,
             title_route::SyltTitleRoutePlugin,
@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+
+use crate::{menus::SyltMenuState, routes::SyltRouterState};
+
+pub struct SyltNewGameRoutePlugin;
+
+impl Plugin for SyltNewGameRoutePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(SyltRouterState::NewGame), open_new_game_menu);
+        app.add_systems(OnExit(SyltRouterState::NewGame), close_new_game_menu);
+    }
+}
+
+fn open_new_game_menu(mut menu_state: ResMut<NextState<SyltMenuState>>) {
+    menu_state.set(SyltMenuState::NewGame);
+}
+
+fn close_new_game_menu(mut menu_state: ResMut<NextState<SyltMenuState>>) {
+    menu_state.set(SyltMenuState::None);
+}
@@ -0,0 +1,26 @@
+use bevy::prelude::*;
+
+use crate::{menus::SyltMenuState, routes::SyltRouterState};
+
+pub struct SyltPrestigeRoutePlugin;
+
+impl Plugin for SyltPrestigeRoutePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(SyltRouterState::Prestige),
+            open_prestige_shop_menu,
+        );
+        app.add_systems(
+            OnExit(SyltRouterState::Prestige),
+            close_prestige_shop_menu,
+        );
+    }
+}
+
+fn open_prestige_shop_menu(mut menu_state: ResMut<NextState<SyltMenuState>>) {
+    menu_state.set(SyltMenuState::PrestigeShop);
+}
+
+fn close_prestige_shop_menu(mut menu_state: ResMut<NextState<SyltMenuState>>) {
+    menu_state.set(SyltMenuState::None);
+}
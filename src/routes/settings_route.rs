@@ -8,6 +8,7 @@ use bevy::{
 use crate::{
     routes::SyltRouterState,
     settings::{
+        gameplay::{SyltNumberNotation, SyltOfflineHours, SyltUndoWindow},
         language::SyltLanguage,
         video::{SyltMsaa, SyltUiScale, SyltVerticalSync, SyltWindowMode},
         volume::{
@@ -110,19 +111,139 @@ fn setup_gameplay_settings(
         )
         .id();
 
-    gameplay_label_cardinal.north = Some(language_select);
+    let offline_hours_wrapper = cmd
+        .spawn((label_layout(), StateScoped(SettingsState::Gameplay)))
+        .id();
+
+    let offline_hours_label_id =
+        cmd.spawn_sylt_label("offline_hours_select", ());
+
+    let offline_hours_select = cmd
+        .spawn_sylt_select(
+            vec![
+                SyltOption {
+                    i18n_key: "offline_hours_off".to_string(),
+                },
+                SyltOption {
+                    i18n_key: "offline_hours_1h".to_string(),
+                },
+                SyltOption {
+                    i18n_key: "offline_hours_4h".to_string(),
+                },
+                SyltOption {
+                    i18n_key: "offline_hours_8h".to_string(),
+                },
+                SyltOption {
+                    i18n_key: "offline_hours_24h".to_string(),
+                },
+            ],
+            settings.offline_hours.into(),
+            SyltOfflineHours,
+            (),
+        )
+        .id();
+
+    let number_notation_wrapper = cmd
+        .spawn((label_layout(), StateScoped(SettingsState::Gameplay)))
+        .id();
+
+    let number_notation_label_id = cmd.spawn_sylt_label("notation_select", ());
+
+    let number_notation_select = cmd
+        .spawn_sylt_select(
+            vec![
+                SyltOption {
+                    i18n_key: "notation_short".to_string(),
+                },
+                SyltOption {
+                    i18n_key: "notation_scientific".to_string(),
+                },
+                SyltOption {
+                    i18n_key: "notation_engineering".to_string(),
+                },
+            ],
+            settings.number_notation.into(),
+            SyltNumberNotation,
+            (),
+        )
+        .id();
+
+    let undo_window_wrapper = cmd
+        .spawn((label_layout(), StateScoped(SettingsState::Gameplay)))
+        .id();
+
+    let undo_window_label_id = cmd.spawn_sylt_label("undo_window_select", ());
+
+    let undo_window_select = cmd
+        .spawn_sylt_select(
+            vec![
+                SyltOption {
+                    i18n_key: "undo_window_off".to_string(),
+                },
+                SyltOption {
+                    i18n_key: "undo_window_10s".to_string(),
+                },
+                SyltOption {
+                    i18n_key: "undo_window_30s".to_string(),
+                },
+                SyltOption {
+                    i18n_key: "undo_window_60s".to_string(),
+                },
+            ],
+            settings.undo_window.into(),
+            SyltUndoWindow,
+            (),
+        )
+        .id();
+
+    gameplay_label_cardinal.north = Some(undo_window_select);
     gameplay_label_cardinal.south = Some(language_select);
 
     cmd.entity(language_select).insert(SyltCardinalNavigation {
         north: Some(*entity),
-        south: Some(*entity),
+        south: Some(offline_hours_select),
         ..default()
     });
 
+    cmd.entity(offline_hours_select)
+        .insert(SyltCardinalNavigation {
+            north: Some(language_select),
+            south: Some(number_notation_select),
+            ..default()
+        });
+
+    cmd.entity(number_notation_select)
+        .insert(SyltCardinalNavigation {
+            north: Some(offline_hours_select),
+            south: Some(undo_window_select),
+            ..default()
+        });
+
+    cmd.entity(undo_window_select)
+        .insert(SyltCardinalNavigation {
+            north: Some(number_notation_select),
+            south: Some(*entity),
+            ..default()
+        });
+
     cmd.entity(language_wrapper)
         .add_children(&[language_label_id, language_select]);
 
-    cmd.entity(*container).add_children(&[language_wrapper]);
+    cmd.entity(offline_hours_wrapper)
+        .add_children(&[offline_hours_label_id, offline_hours_select]);
+
+    cmd.entity(number_notation_wrapper)
+        .add_children(&[number_notation_label_id, number_notation_select]);
+
+    cmd.entity(undo_window_wrapper)
+        .add_children(&[undo_window_label_id, undo_window_select]);
+
+    cmd.entity(*container).add_children(&[
+        language_wrapper,
+        offline_hours_wrapper,
+        number_notation_wrapper,
+        undo_window_wrapper,
+    ]);
 
     cardinal_focus_event_writer.write(SyltCardinalFocusedEvent(Some(*entity)));
 }
@@ -0,0 +1,192 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::currency::NumberNotation, routes::SyltRouterState,
+    ui::components::select::SyltSelect,
+};
+
+use super::{SettingsEvent, SyltSettings};
+
+pub struct SyltGameplayPlugin;
+
+impl Plugin for SyltGameplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                offline_hours_system,
+                number_notation_system,
+                undo_window_system,
+            )
+                .run_if(in_state(SyltRouterState::Settings)),
+        );
+    }
+}
+
+fn offline_hours_system(
+    mut settings: ResMut<SyltSettings>,
+    select_q: Query<&SyltSelect, (With<SyltOfflineHours>, Changed<SyltSelect>)>,
+    mut settings_event_writer: EventWriter<SettingsEvent>,
+) {
+    for select in select_q.iter() {
+        let selected = select.options[select.selected_index]
+            .i18n_key
+            .as_str()
+            .into();
+
+        if settings.offline_hours == selected {
+            continue;
+        }
+
+        settings.offline_hours = selected;
+        settings_event_writer.write(SettingsEvent::Save);
+    }
+}
+
+fn number_notation_system(
+    mut settings: ResMut<SyltSettings>,
+    select_q: Query<
+        &SyltSelect,
+        (With<SyltNumberNotation>, Changed<SyltSelect>),
+    >,
+    mut settings_event_writer: EventWriter<SettingsEvent>,
+) {
+    for select in select_q.iter() {
+        let selected = select.options[select.selected_index]
+            .i18n_key
+            .as_str()
+            .into();
+
+        if settings.number_notation == selected {
+            continue;
+        }
+
+        settings.number_notation = selected;
+        settings_event_writer.write(SettingsEvent::Save);
+    }
+}
+
+fn undo_window_system(
+    mut settings: ResMut<SyltSettings>,
+    select_q: Query<&SyltSelect, (With<SyltUndoWindow>, Changed<SyltSelect>)>,
+    mut settings_event_writer: EventWriter<SettingsEvent>,
+) {
+    for select in select_q.iter() {
+        let selected = select.options[select.selected_index]
+            .i18n_key
+            .as_str()
+            .into();
+
+        if settings.undo_window == selected {
+            continue;
+        }
+
+        settings.undo_window = selected;
+        settings_event_writer.write(SettingsEvent::Save);
+    }
+}
+
+/// Maximum number of hours of progress simulated while the game was closed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SyltOfflineHoursSetting(pub f32);
+
+impl Default for SyltOfflineHoursSetting {
+    fn default() -> Self {
+        Self(8.0)
+    }
+}
+
+#[derive(Component)]
+pub struct SyltOfflineHours;
+
+impl From<SyltOfflineHoursSetting> for &str {
+    fn from(hours: SyltOfflineHoursSetting) -> Self {
+        match hours.0 {
+            0.0 => "offline_hours_off",
+            1.0 => "offline_hours_1h",
+            4.0 => "offline_hours_4h",
+            8.0 => "offline_hours_8h",
+            24.0 => "offline_hours_24h",
+            _ => "offline_hours_8h",
+        }
+    }
+}
+
+impl From<&str> for SyltOfflineHoursSetting {
+    fn from(value: &str) -> Self {
+        match value {
+            "offline_hours_off" => Self(0.0),
+            "offline_hours_1h" => Self(1.0),
+            "offline_hours_4h" => Self(4.0),
+            "offline_hours_8h" => Self(8.0),
+            "offline_hours_24h" => Self(24.0),
+            _ => Self(8.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct SyltNumberNotationSetting(pub NumberNotation);
+
+#[derive(Component)]
+pub struct SyltNumberNotation;
+
+impl From<SyltNumberNotationSetting> for &str {
+    fn from(notation: SyltNumberNotationSetting) -> Self {
+        match notation.0 {
+            NumberNotation::Short => "notation_short",
+            NumberNotation::Scientific => "notation_scientific",
+            NumberNotation::Engineering => "notation_engineering",
+        }
+    }
+}
+
+impl From<&str> for SyltNumberNotationSetting {
+    fn from(value: &str) -> Self {
+        match value {
+            "notation_short" => Self(NumberNotation::Short),
+            "notation_scientific" => Self(NumberNotation::Scientific),
+            "notation_engineering" => Self(NumberNotation::Engineering),
+            _ => Self(NumberNotation::Short),
+        }
+    }
+}
+
+/// Seconds after which an action can no longer be undone, keeps undoing from
+/// turning a node's price into a free loan against idle income
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SyltUndoWindowSetting(pub f32);
+
+impl Default for SyltUndoWindowSetting {
+    fn default() -> Self {
+        Self(30.0)
+    }
+}
+
+#[derive(Component)]
+pub struct SyltUndoWindow;
+
+impl From<SyltUndoWindowSetting> for &str {
+    fn from(window: SyltUndoWindowSetting) -> Self {
+        match window.0 {
+            0.0 => "undo_window_off",
+            10.0 => "undo_window_10s",
+            30.0 => "undo_window_30s",
+            60.0 => "undo_window_60s",
+            _ => "undo_window_30s",
+        }
+    }
+}
+
+impl From<&str> for SyltUndoWindowSetting {
+    fn from(value: &str) -> Self {
+        match value {
+            "undo_window_off" => Self(0.0),
+            "undo_window_10s" => Self(10.0),
+            "undo_window_30s" => Self(30.0),
+            "undo_window_60s" => Self(60.0),
+            _ => Self(30.0),
+        }
+    }
+}
@@ -2,6 +2,9 @@ use bevy::{
     prelude::*,
     window::{PresentMode, WindowMode},
 };
+use gameplay::{
+    SyltNumberNotationSetting, SyltOfflineHoursSetting, SyltUndoWindowSetting,
+};
 use video::{
     SyltMsaaSetting, SyltPresentModeSetting, SyltUiScaleSetting,
     SyltWindowModeSetting,
@@ -9,6 +12,7 @@ use video::{
 
 use crate::{i18n::SyltLocale, signals::SyltSignal};
 
+pub mod gameplay;
 pub mod language;
 pub mod video;
 pub mod volume;
@@ -24,6 +28,7 @@ impl Plugin for SyltSettingsPlugin {
             volume::SyltVolumePlugin,
             language::SyltLanguagePlugin,
             video::SyltVideoPlugin,
+            gameplay::SyltGameplayPlugin,
         ));
         app.init_resource::<SyltSettings>();
         app.add_systems(Startup, trigger_load_settings);
@@ -56,6 +61,12 @@ pub struct SyltSettings {
     pub window_mode: SyltWindowModeSetting,
     pub vsync: SyltPresentModeSetting,
     pub ui_scale: SyltUiScaleSetting,
+    #[serde(default)]
+    pub offline_hours: SyltOfflineHoursSetting,
+    #[serde(default)]
+    pub number_notation: SyltNumberNotationSetting,
+    #[serde(default)]
+    pub undo_window: SyltUndoWindowSetting,
 }
 
 impl Default for SyltSettings {
@@ -71,6 +82,9 @@ impl Default for SyltSettings {
             window_mode: SyltWindowModeSetting(WindowMode::Windowed),
             vsync: SyltPresentModeSetting(PresentMode::AutoNoVsync),
             ui_scale: SyltUiScaleSetting(1.0),
+            offline_hours: SyltOfflineHoursSetting::default(),
+            number_notation: SyltNumberNotationSetting::default(),
+            undo_window: SyltUndoWindowSetting::default(),
         }
     }
 }
@@ -367,7 +367,7 @@ fn reset_crosshair(
 
 fn tick_timers(
     mut focus_crosshair_q: Query<&mut CardinalCrosshair>,
-    time: Res<Time>,
+    time: Res<Time<Real>>,
 ) {
     for mut focus_crosshair in focus_crosshair_q.iter_mut() {
         //focus_crosshair.cooldown_timer.tick(time.delta());
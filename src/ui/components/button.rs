@@ -77,19 +77,10 @@ pub trait SyltButtonNavigationExt {
 
 impl SyltButtonNavigationExt for EntityCommands<'_> {
     fn navigate_on_click(&mut self, route: SyltRouterState) -> &mut Self {
-        let route1 = route.clone();
-        let route2 = route.clone();
-
         self.observe(
             move |_: Trigger<SyltButtonPressed>,
                   mut n: ResMut<NextState<SyltRouterState>>| {
-                n.set(route1.clone());
-            },
-        )
-        .observe(
-            move |_: Trigger<Pointer<Released>>,
-                  mut n: ResMut<NextState<SyltRouterState>>| {
-                n.set(route2.clone());
+                n.set(route.clone());
             },
         )
     }
@@ -36,7 +36,7 @@ fn render_focus_animation(
         (Entity, &ComputedNode, &mut SyltUiScene),
         With<SyltFocusPrimary>,
     >,
-    time: Res<Time>,
+    time: Res<Time<Real>>,
     cardinal_focus: Res<SyltCardinalFocusedResource>,
 ) {
     for (entity, node, mut scene) in button_q.iter_mut() {
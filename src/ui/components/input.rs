@@ -389,7 +389,7 @@ fn input_copy_paste_system(
 pub struct SyltInputCursor;
 
 fn draw_cursor(
-    time: Res<Time>,
+    time: Res<Time<Real>>,
 
     mut input_q: Query<
         (Entity, &Children),
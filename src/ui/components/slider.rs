@@ -92,7 +92,7 @@ impl SyltSliderExt for Commands<'_, '_> {
 
 // TODO: gardient slider
 fn render_slider(
-    time: Res<Time>,
+    time: Res<Time<Real>>,
     mut left_arrow_q: Query<(
         &ComputedNode,
         &mut SyltUiScene,
@@ -134,3 +134,23 @@ pub fn ease_in_out_bounce_f64(t: f64) -> f64 {
         (1.0 + ease_out_bounce_f64(2.0 * t - 1.0)) / 2.0
     }
 }
+
+/// https://easings.net/#easeInCubic
+pub fn ease_in_cubic(t: f32) -> f32 {
+    t.clamp(0.0, 1.0).powi(3)
+}
+
+/// https://easings.net/#easeInCubic
+pub fn ease_in_cubic_f64(t: f64) -> f64 {
+    t.clamp(0.0, 1.0).powi(3)
+}
+
+/// https://easings.net/#easeOutCubic
+pub fn ease_out_cubic(t: f32) -> f32 {
+    1.0 - (1.0 - t.clamp(0.0, 1.0)).powi(3)
+}
+
+/// https://easings.net/#easeOutCubic
+pub fn ease_out_cubic_f64(t: f64) -> f64 {
+    1.0 - (1.0 - t.clamp(0.0, 1.0)).powi(3)
+}